@@ -0,0 +1,242 @@
+//! Generates the UnitType, Ability, Upgrade, Buff, and EffectId enums from
+//! ids/stableid.json.
+//!
+//! The file uses a custom format that is modeled on the stableid.json that
+//! ships with the game, but cannot be swapped for it. Each section is a list
+//! of id/name pairs where the names are the Rust variant names (ie.
+//! "TerranMarine" where the game has "Marine"), and units can also have an
+//! abilities list (see below). When a patch adds new ids, add them to the
+//! file by hand and rebuild. Ids that are not listed still round-trip
+//! through the Unknown variant of each enum, so an outdated list is not
+//! fatal.
+//!
+//! The abilities of a unit are the ones it is able to use. The game data does
+//! not include them, but the tech tree needs them to figure out what builds
+//! what.
+
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use std::collections::HashSet;
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const IDS_PATH: &str = "ids/stableid.json";
+
+#[derive(Deserialize)]
+struct StableIds {
+    #[serde(rename = "Units")]
+    units: Vec<StableId>,
+    #[serde(rename = "Abilities")]
+    abilities: Vec<StableId>,
+    #[serde(rename = "Upgrades")]
+    upgrades: Vec<StableId>,
+    #[serde(rename = "Buffs")]
+    buffs: Vec<StableId>,
+    #[serde(rename = "Effects")]
+    effects: Vec<StableId>,
+}
+
+#[derive(Deserialize)]
+struct StableId {
+    id: u32,
+    name: String,
+    /// Abilities that units of this type can use (only used for units).
+    #[serde(default)]
+    abilities: Vec<String>,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", IDS_PATH);
+
+    let mut json = String::new();
+    File::open(IDS_PATH)
+        .and_then(|mut f| f.read_to_string(&mut json))
+        .expect("unable to read stable ids");
+
+    let ids: StableIds =
+        serde_json::from_str(&json).expect("unable to parse stable ids");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    write_enum(
+        &out_dir,
+        "unit_type.rs",
+        "UnitType",
+        "List of known StarCraft II unit types.",
+        &ids.units,
+    );
+    write_enum(
+        &out_dir,
+        "ability.rs",
+        "Ability",
+        "List of known StarCraft II abilities.",
+        &ids.abilities,
+    );
+    write_enum(
+        &out_dir,
+        "upgrade.rs",
+        "Upgrade",
+        "List of known StarCraft II upgrades.",
+        &ids.upgrades,
+    );
+    write_enum(
+        &out_dir,
+        "buff.rs",
+        "Buff",
+        "List of known StarCraft II buffs.",
+        &ids.buffs,
+    );
+    write_unit_abilities(&out_dir, &ids.units, &ids.abilities);
+    write_enum(
+        &out_dir,
+        "effect.rs",
+        "EffectId",
+        "List of known StarCraft II effects.",
+        &ids.effects,
+    );
+}
+
+/// Write an id enum along with its protobuf conversions.
+fn write_enum(
+    out_dir: &str,
+    file: &str,
+    name: &str,
+    doc: &str,
+    ids: &[StableId],
+) {
+    validate(name, ids);
+
+    let mut variants = String::new();
+    let mut from_arms = String::new();
+    let mut into_arms = String::new();
+
+    for id in ids {
+        variants.push_str(&format!("    {},\n", id.name));
+        from_arms.push_str(&format!(
+            "            {} => {}::{},\n",
+            id.id, name, id.name
+        ));
+        into_arms.push_str(&format!(
+            "            {}::{} => {},\n",
+            name, id.name, id.id
+        ));
+    }
+
+    let src = format!(
+        r#"/// {doc}
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum {name} {{
+{variants}    /// An id that is not in the list of known ids.
+    ///
+    /// This usually means that the game is newer than ids/stableid.json.
+    Unknown(u32),
+}}
+
+impl FromProto<u32> for {name} {{
+    fn from_proto(id: u32) -> Result<Self> {{
+        Ok(match id {{
+{from_arms}            id => {name}::Unknown(id),
+        }})
+    }}
+}}
+
+impl IntoProto<u32> for {name} {{
+    fn into_proto(self) -> Result<u32> {{
+        Ok(match self {{
+{into_arms}            {name}::Unknown(id) => id,
+        }})
+    }}
+}}
+"#,
+        doc = doc,
+        name = name,
+        variants = variants,
+        from_arms = from_arms,
+        into_arms = into_arms,
+    );
+
+    File::create(Path::new(out_dir).join(file))
+        .and_then(|mut f| f.write_all(src.as_bytes()))
+        .expect("unable to write generated ids");
+}
+
+/// Write the lookup table for UnitType::get_abilities.
+fn write_unit_abilities(
+    out_dir: &str,
+    units: &[StableId],
+    abilities: &[StableId],
+) {
+    let known: HashSet<&str> = abilities.iter().map(|a| &a.name[..]).collect();
+
+    let mut arms = String::new();
+
+    for unit in units.iter().filter(|u| !u.abilities.is_empty()) {
+        for ability in &unit.abilities {
+            if !known.contains(&ability[..]) {
+                panic!("{} has an unknown ability {}", unit.name, ability);
+            }
+        }
+
+        let list = unit.abilities
+            .iter()
+            .map(|a| format!("Ability::{}", a))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        arms.push_str(&format!(
+            "            UnitType::{} => &[{}],\n",
+            unit.name, list
+        ));
+    }
+
+    let src = format!(
+        r#"impl UnitType {{
+    /// Abilities that units of this type are able to use.
+    ///
+    /// This comes from ids/stableid.json rather than the game, so it may be
+    /// incomplete for some of the more obscure units.
+    pub fn get_abilities(&self) -> &'static [Ability] {{
+        match *self {{
+{arms}            _ => &[],
+        }}
+    }}
+}}
+"#,
+        arms = arms,
+    );
+
+    File::create(Path::new(out_dir).join("unit_abilities.rs"))
+        .and_then(|mut f| f.write_all(src.as_bytes()))
+        .expect("unable to write generated unit abilities");
+}
+
+/// Make sure the ids can actually be turned into a valid enum.
+fn validate(name: &str, ids: &[StableId]) {
+    let mut seen_ids = HashSet::new();
+    let mut seen_names = HashSet::new();
+
+    for id in ids {
+        let is_ident = id.name
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_ascii_alphabetic())
+            && id.name.chars().all(|c| c.is_ascii_alphanumeric());
+
+        if !is_ident || id.name == "Unknown" {
+            panic!("{} has an invalid variant name {:?}", name, id.name);
+        }
+        if !seen_ids.insert(id.id) {
+            panic!("{} has a duplicate id {}", name, id.id);
+        }
+        if !seen_names.insert(&id.name) {
+            panic!("{} has a duplicate variant {}", name, id.name);
+        }
+    }
+}
@@ -15,7 +15,6 @@ extern crate tokio_core;
 
 extern crate sc2;
 
-use std::f32;
 use std::mem;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -27,6 +26,7 @@ use sc2::{
     action::{Action, ActionClient, ActionTarget},
     agent::AgentBuilder,
     ai::OpponentBuilder,
+    analysis::UnitFilter,
     data::{
         Ability,
         Alliance,
@@ -274,22 +274,10 @@ fn get_nearest_enemy(
     observation: &Observation,
     pos: Point2,
 ) -> Option<Rc<Unit>> {
-    let units =
-        observation.filter_units(|u| u.get_alliance() == Alliance::Enemy);
-
-    let mut min = f32::MAX;
-    let mut nearest = None;
-
-    for u in units {
-        let d = na::distance_squared(&u.get_pos_2d(), &pos);
-
-        if d < min {
-            min = d;
-            nearest = Some(u);
-        }
-    }
-
-    nearest
+    observation
+        .nearest_n(pos, 1, &UnitFilter::new().alliance(Alliance::Enemy))
+        .into_iter()
+        .next()
 }
 
 quick_main!(|| -> sc2::Result<()> {
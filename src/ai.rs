@@ -5,16 +5,18 @@ use futures::unsync::mpsc;
 use tokio_core::reactor;
 
 use constants::sc2_bug_tag;
-use data::{Difficulty, PlayerSetup, Race};
+use data::{AIBuild, Difficulty, PlayerSetup, Race};
 use services::computer_service::ComputerService;
 use services::melee_service::{MeleeCompetitor, MeleeRequest};
 
 use Result;
 
 /// Build a built-in AI opponent.
+#[derive(Debug, Clone)]
 pub struct OpponentBuilder {
     race: Race,
     difficulty: Difficulty,
+    build: AIBuild,
 }
 
 impl OpponentBuilder {
@@ -23,6 +25,7 @@ impl OpponentBuilder {
         Self {
             race: Race::Random,
             difficulty: Difficulty::Medium,
+            build: AIBuild::Random,
         }
     }
 
@@ -41,6 +44,14 @@ impl OpponentBuilder {
             ..self
         }
     }
+
+    /// Set the strategy of the AI (default is Random).
+    pub fn build(self, build: AIBuild) -> Self {
+        Self {
+            build: build,
+            ..self
+        }
+    }
 }
 
 impl MeleeCompetitor for OpponentBuilder {
@@ -53,6 +64,7 @@ impl MeleeCompetitor for OpponentBuilder {
             ComputerService::new(PlayerSetup::Computer(
                 self.race,
                 self.difficulty,
+                self.build,
             )).run(control_rx)
                 .map_err(|e| {
                     panic!(
@@ -0,0 +1,639 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use data::{
+    Unit,
+    UnitType,
+    UnitTypeData,
+    Upgrade,
+    Weapon,
+    WeaponTargetType,
+};
+
+/// Length of each step of the simulation (in game seconds).
+const TIME_STEP: f32 = 0.1;
+/// Fights that take longer than this are called a draw.
+const DEFAULT_TIME_LIMIT: f32 = 60.0;
+/// Damage is never reduced below this by armor.
+const MIN_DAMAGE: f32 = 0.5;
+
+/// A unit taking part in a simulated fight.
+#[derive(Debug, Clone)]
+pub struct CombatUnit {
+    unit_type: UnitType,
+    health: f32,
+    shield: f32,
+    is_flying: bool,
+}
+
+impl CombatUnit {
+    /// A ground unit of the given type.
+    ///
+    /// The game data does not include the max health and shields of a unit
+    /// type, so they need to be given here.
+    pub fn new(unit_type: UnitType, health: f32, shield: f32) -> Self {
+        Self {
+            unit_type: unit_type,
+            health: health,
+            shield: shield,
+            is_flying: false,
+        }
+    }
+
+    /// Whether the unit is in the air (only anti-air weapons can hit it).
+    pub fn flying(self, is_flying: bool) -> Self {
+        Self {
+            is_flying: is_flying,
+            ..self
+        }
+    }
+
+    /// The type of unit.
+    pub fn get_unit_type(&self) -> UnitType {
+        self.unit_type
+    }
+    /// Remaining health of the unit.
+    pub fn get_health(&self) -> f32 {
+        self.health
+    }
+    /// Remaining shields of the unit.
+    pub fn get_shield(&self) -> f32 {
+        self.shield
+    }
+    /// Whether the unit is in the air.
+    pub fn is_flying(&self) -> bool {
+        self.is_flying
+    }
+}
+
+impl<'a> From<&'a Unit> for CombatUnit {
+    fn from(unit: &'a Unit) -> Self {
+        CombatUnit::new(
+            unit.get_unit_type(),
+            unit.get_health(),
+            unit.get_shield(),
+        ).flying(unit.is_flying())
+    }
+}
+
+/// One side of a simulated fight.
+///
+/// Upgrades are applied as a flat bonus to every unit in the army (+1 damage
+/// per attack and +1 armor per level). Keep in mind that the unit data from
+/// the game already includes the upgrades of the current player, so these
+/// are usually only needed for the enemy.
+#[derive(Debug, Clone, Default)]
+pub struct Army {
+    units: Vec<CombatUnit>,
+
+    attack_level: u32,
+    armor_level: u32,
+    shield_level: u32,
+}
+
+impl Army {
+    /// Create an empty army.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a unit to the army.
+    pub fn unit(mut self, unit: CombatUnit) -> Self {
+        self.units.push(unit);
+
+        self
+    }
+    /// Add several copies of a unit to the army.
+    pub fn units_of(mut self, unit: CombatUnit, count: usize) -> Self {
+        for _ in 0..count {
+            self.units.push(unit.clone());
+        }
+
+        self
+    }
+    /// Add observed units to the army.
+    pub fn units(mut self, units: &[Rc<Unit>]) -> Self {
+        self.units.extend(units.iter().map(|u| CombatUnit::from(&**u)));
+
+        self
+    }
+
+    /// Weapon upgrade level.
+    pub fn attack_level(self, level: u32) -> Self {
+        Self {
+            attack_level: level,
+            ..self
+        }
+    }
+    /// Armor upgrade level.
+    pub fn armor_level(self, level: u32) -> Self {
+        Self {
+            armor_level: level,
+            ..self
+        }
+    }
+    /// Shield upgrade level.
+    pub fn shield_level(self, level: u32) -> Self {
+        Self {
+            shield_level: level,
+            ..self
+        }
+    }
+    /// Set the upgrade levels from a list of researched upgrades.
+    ///
+    /// The highest level of each kind of upgrade is used, regardless of
+    /// which units it actually applies to.
+    pub fn upgrades(mut self, upgrades: &[Upgrade]) -> Self {
+        for upgrade in upgrades {
+            if let Some((kind, level)) = get_upgrade_level(*upgrade) {
+                let current = match kind {
+                    UpgradeKind::Attack => &mut self.attack_level,
+                    UpgradeKind::Armor => &mut self.armor_level,
+                    UpgradeKind::Shield => &mut self.shield_level,
+                };
+
+                *current = (*current).max(level);
+            }
+        }
+
+        self
+    }
+
+    /// Units in the army.
+    pub fn get_units(&self) -> &[CombatUnit] {
+        &self.units
+    }
+}
+
+/// Who won a simulated fight.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CombatOutcome {
+    /// Only our army has units left.
+    Victory,
+    /// Only the enemy army has units left.
+    Defeat,
+    /// Both armies died, neither army is able to hurt the other, or the
+    /// time limit was reached.
+    Draw,
+}
+
+/// Prediction of a fight.
+#[derive(Debug, Clone)]
+pub struct CombatResult {
+    outcome: CombatOutcome,
+    survivors: Vec<CombatUnit>,
+    enemy_survivors: Vec<CombatUnit>,
+    duration: f32,
+}
+
+impl CombatResult {
+    /// Who won the fight.
+    pub fn get_outcome(&self) -> CombatOutcome {
+        self.outcome
+    }
+    /// Our units that survived (with their remaining health and shields).
+    pub fn get_survivors(&self) -> &[CombatUnit] {
+        &self.survivors
+    }
+    /// Enemy units that survived.
+    pub fn get_enemy_survivors(&self) -> &[CombatUnit] {
+        &self.enemy_survivors
+    }
+    /// How long the fight took in game seconds (the time to kill the losing
+    /// army).
+    pub fn get_duration(&self) -> f32 {
+        self.duration
+    }
+}
+
+/// Predicts the outcome of fights without running the game.
+///
+/// The simulation assumes that every unit is in range of the enemy for the
+/// entire fight, and that units focus fire on the weakest target they can
+/// hit. Splash damage is approximated by hitting a few extra targets. It
+/// ignores positioning, spells, and micro, so it is only a rough estimate of
+/// whether a fight is worth taking.
+#[derive(Debug, Clone)]
+pub struct CombatSimulator {
+    unit_data: Rc<HashMap<UnitType, UnitTypeData>>,
+    time_limit: f32,
+}
+
+impl CombatSimulator {
+    /// Create a simulator from the game data.
+    pub fn new(unit_data: Rc<HashMap<UnitType, UnitTypeData>>) -> Self {
+        Self {
+            unit_data: unit_data,
+            time_limit: DEFAULT_TIME_LIMIT,
+        }
+    }
+
+    /// Longest fight to simulate in game seconds (defaults to 60).
+    pub fn time_limit(self, seconds: f32) -> Self {
+        Self {
+            time_limit: seconds,
+            ..self
+        }
+    }
+
+    /// Simulate a fight between our army and the enemy army.
+    pub fn simulate(&self, army: &Army, enemy: &Army) -> CombatResult {
+        let armies = [army, enemy];
+        let mut sides = vec![self.prepare(army), self.prepare(enemy)];
+        let mut time = 0.0;
+
+        while time < self.time_limit {
+            if !sides.iter().all(|s| s.iter().any(|f| f.is_alive())) {
+                break;
+            }
+
+            let mut engaged = false;
+            let mut fired = vec![];
+            let mut hits = vec![];
+
+            for side in 0..2 {
+                let attackers = &sides[side];
+                let targets = &sides[1 - side];
+
+                let mut pending = vec![0.0; targets.len()];
+
+                for (i, attacker) in attackers.iter().enumerate() {
+                    if !attacker.is_alive() {
+                        continue;
+                    }
+
+                    let (target, weapon) =
+                        match attacker.choose_target(targets, &pending) {
+                            Some(choice) => choice,
+                            None => continue,
+                        };
+
+                    engaged = true;
+
+                    if attacker.cooldown > 0.0 {
+                        continue;
+                    }
+
+                    let mut victims = vec![target];
+
+                    victims.extend(
+                        targets
+                            .iter()
+                            .enumerate()
+                            .filter(|&(j, t)| {
+                                j != target && t.is_alive()
+                                    && can_hit(weapon, t.unit.is_flying)
+                            })
+                            .map(|(j, _)| j)
+                            .take(get_splash_targets(attacker.unit.unit_type)),
+                    );
+
+                    for victim in victims {
+                        let damage = get_damage(
+                            weapon,
+                            armies[side].attack_level,
+                            targets[victim].data,
+                        );
+
+                        for _ in 0..weapon.get_attacks().max(1) {
+                            hits.push((1 - side, victim, damage));
+                        }
+
+                        pending[victim] +=
+                            damage * weapon.get_attacks().max(1) as f32;
+                    }
+
+                    fired.push((side, i, weapon.get_speed()));
+                }
+            }
+
+            if !engaged {
+                break;
+            }
+
+            for (side, victim, damage) in hits {
+                let armor = armies[side].armor_level as f32;
+                let shield_armor = armies[side].shield_level as f32;
+
+                sides[side][victim].take_hit(damage, armor, shield_armor);
+            }
+            for (side, i, speed) in fired {
+                sides[side][i].cooldown += speed;
+            }
+            for fighter in sides.iter_mut().flat_map(|s| s.iter_mut()) {
+                fighter.cooldown = (fighter.cooldown - TIME_STEP).max(0.0);
+            }
+
+            time += TIME_STEP;
+        }
+
+        let theirs = sides.pop().unwrap();
+        let ours = sides.pop().unwrap();
+
+        let survivors = ours
+            .into_iter()
+            .filter(|f| f.is_alive())
+            .map(|f| f.unit)
+            .collect::<Vec<_>>();
+        let enemy_survivors = theirs
+            .into_iter()
+            .filter(|f| f.is_alive())
+            .map(|f| f.unit)
+            .collect::<Vec<_>>();
+
+        let outcome = if enemy_survivors.is_empty() && !survivors.is_empty() {
+            CombatOutcome::Victory
+        } else if survivors.is_empty() && !enemy_survivors.is_empty() {
+            CombatOutcome::Defeat
+        } else {
+            CombatOutcome::Draw
+        };
+
+        CombatResult {
+            outcome: outcome,
+            survivors: survivors,
+            enemy_survivors: enemy_survivors,
+            duration: time,
+        }
+    }
+
+    fn prepare<'a>(&'a self, army: &Army) -> Vec<Fighter<'a>> {
+        army.units
+            .iter()
+            .map(|unit| Fighter {
+                unit: unit.clone(),
+                data: self.unit_data.get(&unit.unit_type),
+                cooldown: 0.0,
+            })
+            .collect()
+    }
+}
+
+/// Simulation state of a unit.
+struct Fighter<'a> {
+    unit: CombatUnit,
+    data: Option<&'a UnitTypeData>,
+    cooldown: f32,
+}
+
+impl<'a> Fighter<'a> {
+    fn is_alive(&self) -> bool {
+        self.unit.health > 0.0
+    }
+
+    fn get_weapons(&self) -> &'a [Weapon] {
+        match self.data {
+            Some(data) => data.get_weapons(),
+            None => &[],
+        }
+    }
+
+    /// Pick the living target with the least health left after the damage
+    /// that is already on its way, along with the weapon to use on it.
+    fn choose_target(
+        &self,
+        targets: &[Fighter],
+        pending: &[f32],
+    ) -> Option<(usize, &'a Weapon)> {
+        let mut best: Option<(bool, f32, usize, &'a Weapon)> = None;
+
+        for (i, target) in targets.iter().enumerate() {
+            if !target.is_alive() {
+                continue;
+            }
+
+            let weapon = match self
+                .get_weapons()
+                .iter()
+                .find(|w| can_hit(w, target.unit.is_flying))
+            {
+                Some(weapon) => weapon,
+                None => continue,
+            };
+
+            let left = target.unit.health + target.unit.shield - pending[i];
+            // avoid overkill by preferring targets that are not already dead
+            let key = (left <= 0.0, left);
+
+            let is_better = match best {
+                Some((dead, remaining, _, _)) => key < (dead, remaining),
+                None => true,
+            };
+
+            if is_better {
+                best = Some((key.0, key.1, i, weapon));
+            }
+        }
+
+        best.map(|(_, _, i, weapon)| (i, weapon))
+    }
+
+    /// Shields absorb damage first, then armor reduces the rest.
+    fn take_hit(&mut self, damage: f32, armor_level: f32, shield_armor: f32) {
+        let mut damage = damage;
+
+        if self.unit.shield > 0.0 {
+            let absorbed = (damage - shield_armor).max(MIN_DAMAGE);
+
+            if absorbed <= self.unit.shield {
+                self.unit.shield -= absorbed;
+                return;
+            }
+
+            damage = absorbed - self.unit.shield;
+            self.unit.shield = 0.0;
+        }
+
+        let armor = self.data.map_or(0.0, |d| d.get_armor()) + armor_level;
+
+        self.unit.health -= (damage - armor).max(MIN_DAMAGE);
+    }
+}
+
+fn can_hit(weapon: &Weapon, is_flying: bool) -> bool {
+    match weapon.get_target_type() {
+        WeaponTargetType::Any => true,
+        WeaponTargetType::Air => is_flying,
+        WeaponTargetType::Ground => !is_flying,
+    }
+}
+
+/// Damage of a single attack of the weapon (before armor).
+fn get_damage(
+    weapon: &Weapon,
+    attack_level: u32,
+    target: Option<&UnitTypeData>,
+) -> f32 {
+    let bonus = match target {
+        Some(target) => weapon
+            .get_damage_bonus()
+            .iter()
+            .filter(|b| target.get_attributes().contains(&b.get_attribute()))
+            .map(|b| b.get_bonus())
+            .sum::<f32>(),
+        None => 0.0,
+    };
+
+    weapon.get_damage() + bonus + attack_level as f32
+}
+
+/// Rough number of extra units hit by each attack of units with splash
+/// damage (assuming the enemy army is reasonably clumped up).
+fn get_splash_targets(unit_type: UnitType) -> usize {
+    match unit_type {
+        UnitType::ZergBaneling => 4,
+        UnitType::TerranSiegeTankSieged
+        | UnitType::ZergLurkerMpBurrowed => 3,
+        UnitType::ProtossColossus
+        | UnitType::ProtossArchon
+        | UnitType::TerranHellion
+        | UnitType::TerranHellionTank
+        | UnitType::TerranThor
+        | UnitType::TerranLiberator
+        | UnitType::ZergUltralisk => 2,
+        UnitType::ZergMutalisk => 1,
+        _ => 0,
+    }
+}
+
+enum UpgradeKind {
+    Attack,
+    Armor,
+    Shield,
+}
+
+fn get_upgrade_level(upgrade: Upgrade) -> Option<(UpgradeKind, u32)> {
+    Some(match upgrade {
+        Upgrade::TerranInfantryWeaponsLevel1
+        | Upgrade::TerranVehicleWeaponsLevel1
+        | Upgrade::TerranShipWeaponsLevel1
+        | Upgrade::ProtossGroundWeaponsLevel1
+        | Upgrade::ProtossAirWeaponsLevel1
+        | Upgrade::ZergMeleeWeaponsLevel1
+        | Upgrade::ZergMissileWeaponsLevel1
+        | Upgrade::ZergFlyerWeaponsLevel1 => (UpgradeKind::Attack, 1),
+        Upgrade::TerranInfantryWeaponsLevel2
+        | Upgrade::TerranVehicleWeaponsLevel2
+        | Upgrade::TerranShipWeaponsLevel2
+        | Upgrade::ProtossGroundWeaponsLevel2
+        | Upgrade::ProtossAirWeaponsLevel2
+        | Upgrade::ZergMeleeWeaponsLevel2
+        | Upgrade::ZergMissileWeaponsLevel2
+        | Upgrade::ZergFlyerWeaponsLevel2 => (UpgradeKind::Attack, 2),
+        Upgrade::TerranInfantryWeaponsLevel3
+        | Upgrade::TerranVehicleWeaponsLevel3
+        | Upgrade::TerranShipWeaponsLevel3
+        | Upgrade::ProtossGroundWeaponsLevel3
+        | Upgrade::ProtossAirWeaponsLevel3
+        | Upgrade::ZergMeleeWeaponsLevel3
+        | Upgrade::ZergMissileWeaponsLevel3
+        | Upgrade::ZergFlyerWeaponsLevel3 => (UpgradeKind::Attack, 3),
+
+        Upgrade::TerranInfantryArmorsLevel1
+        | Upgrade::TerranVehicleAndShipArmorsLevel1
+        | Upgrade::ProtossGroundArmorsLevel1
+        | Upgrade::ProtossAirArmorsLevel1
+        | Upgrade::ZergGroundArmorsLevel1
+        | Upgrade::ZergFlyerArmorsLevel1 => (UpgradeKind::Armor, 1),
+        Upgrade::TerranInfantryArmorsLevel2
+        | Upgrade::TerranVehicleAndShipArmorsLevel2
+        | Upgrade::ProtossGroundArmorsLevel2
+        | Upgrade::ProtossAirArmorsLevel2
+        | Upgrade::ZergGroundArmorsLevel2
+        | Upgrade::ZergFlyerArmorsLevel2 => (UpgradeKind::Armor, 2),
+        Upgrade::TerranInfantryArmorsLevel3
+        | Upgrade::TerranVehicleAndShipArmorsLevel3
+        | Upgrade::ProtossGroundArmorsLevel3
+        | Upgrade::ProtossAirArmorsLevel3
+        | Upgrade::ZergGroundArmorsLevel3
+        | Upgrade::ZergFlyerArmorsLevel3 => (UpgradeKind::Armor, 3),
+
+        Upgrade::ProtossShieldsLevel1 => (UpgradeKind::Shield, 1),
+        Upgrade::ProtossShieldsLevel2 => (UpgradeKind::Shield, 2),
+        Upgrade::ProtossShieldsLevel3 => (UpgradeKind::Shield, 3),
+
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::data;
+
+    use FromProto;
+
+    fn unit_data(
+        unit_type: u32,
+        target: data::Weapon_TargetType,
+        damage: f32,
+    ) -> (UnitType, UnitTypeData) {
+        let mut weapon = data::Weapon::new();
+
+        weapon.set_field_type(target);
+        weapon.set_damage(damage);
+        weapon.set_attacks(1);
+        weapon.set_range(5.0);
+        weapon.set_speed(0.61);
+
+        let mut data = data::UnitTypeData::new();
+
+        data.set_unit_id(unit_type);
+        data.mut_weapons().push(weapon);
+
+        let data = UnitTypeData::from_proto(data).unwrap();
+
+        (data.get_id(), data)
+    }
+
+    fn simulator() -> CombatSimulator {
+        CombatSimulator::new(Rc::from(
+            vec![
+                // Marine
+                unit_data(48, data::Weapon_TargetType::Any, 6.0),
+                // Zergling
+                unit_data(105, data::Weapon_TargetType::Ground, 5.0),
+            ].into_iter()
+                .collect::<HashMap<_, _>>(),
+        ))
+    }
+
+    #[test]
+    fn test_bigger_army_wins() {
+        let marine = CombatUnit::new(UnitType::TerranMarine, 45.0, 0.0);
+
+        let result = simulator().simulate(
+            &Army::new().units_of(marine.clone(), 10),
+            &Army::new().units_of(marine.clone(), 5),
+        );
+
+        assert_eq!(result.get_outcome(), CombatOutcome::Victory);
+        assert!(!result.get_survivors().is_empty());
+        assert!(result.get_enemy_survivors().is_empty());
+        assert!(result.get_duration() > 0.0);
+
+        // upgrades can turn the fight around
+        let result = simulator().simulate(
+            &Army::new().units_of(marine.clone(), 5),
+            &Army::new().units_of(marine.clone(), 5).attack_level(3),
+        );
+
+        assert_eq!(result.get_outcome(), CombatOutcome::Defeat);
+    }
+
+    #[test]
+    fn test_cannot_hit_air() {
+        let zergling = CombatUnit::new(UnitType::ZergZergling, 35.0, 0.0);
+        let overlord =
+            CombatUnit::new(UnitType::ZergOverlord, 200.0, 0.0).flying(true);
+
+        let result = simulator().simulate(
+            &Army::new().unit(zergling),
+            &Army::new().unit(overlord),
+        );
+
+        assert_eq!(result.get_outcome(), CombatOutcome::Draw);
+        assert_eq!(result.get_duration(), 0.0);
+    }
+}
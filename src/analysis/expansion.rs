@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use analysis::grid::{tile_center, to_tile};
+use analysis::pathing::ground_distances;
+use data::{Alliance, MapInfo, Point2, Point2I, Unit};
+use observer::Observation;
+
+/// Resources closer than this to each other belong to the same base.
+const RESOURCE_LINK_DISTANCE: f32 = 10.0;
+/// Groups with fewer resources are ignored (ie. mineral walls).
+const MIN_RESOURCES: usize = 4;
+/// How far away from the resources to look for a town hall placement.
+const SEARCH_RADIUS: i32 = 12;
+/// Town halls cannot be placed closer than this to a mineral field.
+const MINERAL_CLEARANCE: f32 = 6.0;
+/// Town halls cannot be placed closer than this to a vespene geyser.
+const GEYSER_CLEARANCE: f32 = 7.0;
+/// Half the width of a town hall footprint (5x5).
+const TOWNHALL_HALF_SIZE: i32 = 2;
+/// Start locations within this distance of an expansion belong to it.
+const START_LOCATION_DISTANCE: f32 = 8.0;
+
+/// A base location and the resources that belong to it.
+#[derive(Debug, Clone)]
+pub struct Expansion {
+    center: Point2,
+    resources: Vec<Rc<Unit>>,
+
+    ground_distance: Option<f32>,
+    is_start_location: bool,
+    is_enemy_start_location: bool,
+}
+
+impl Expansion {
+    /// Best placement for a town hall.
+    pub fn get_center(&self) -> Point2 {
+        self.center
+    }
+    /// Mineral fields and vespene geysers of this expansion.
+    pub fn get_resources(&self) -> &[Rc<Unit>] {
+        &self.resources
+    }
+    /// Ground distance from our start location (None if unreachable).
+    pub fn get_ground_distance(&self) -> Option<f32> {
+        self.ground_distance
+    }
+    /// Whether this is our start location.
+    pub fn is_start_location(&self) -> bool {
+        self.is_start_location
+    }
+    /// Whether this is a possible enemy start location.
+    pub fn is_enemy_start_location(&self) -> bool {
+        self.is_enemy_start_location
+    }
+}
+
+/// Find every expansion on the map.
+///
+/// This should be called with the first observation of the game, since the
+/// resources are all visible (as snapshots) at that point. Expansions are
+/// sorted by ground distance from our start location, so the first one is
+/// our main base and the second one is usually the natural.
+pub fn find_expansions(
+    map_info: &MapInfo,
+    observation: &Observation,
+) -> Vec<Expansion> {
+    let resources = observation.filter_units(|u| {
+        let unit_type = u.get_unit_type();
+
+        unit_type.is_mineral_field() || unit_type.is_vespene_geyser()
+    });
+
+    let start = observation
+        .get_units()
+        .iter()
+        .find(|u| {
+            u.get_alliance() == Alliance::Domestic
+                && u.get_unit_type().is_townhall()
+        })
+        .map(|u| u.get_pos_2d());
+
+    let distances = start.map(|start| ground_distances(map_info, start));
+
+    let mut expansions = vec![];
+
+    for group in group_resources(resources) {
+        if group.len() < MIN_RESOURCES {
+            continue;
+        }
+
+        let mut center = match find_townhall_placement(map_info, &group) {
+            Some(center) => center,
+            None => continue,
+        };
+
+        let is_start_location = match start {
+            Some(start) => is_near(start, center),
+            None => false,
+        };
+        let enemy_start = map_info
+            .get_enemy_start_locations()
+            .iter()
+            .find(|p| is_near(**p, center))
+            .map(|p| *p);
+
+        // start locations are known exactly
+        if is_start_location {
+            center = start.unwrap();
+        } else if let Some(enemy_start) = enemy_start {
+            center = enemy_start;
+        }
+
+        let ground_distance = distances.as_ref().and_then(|d| {
+            match d.get(to_tile(center)) {
+                Some(distance) if distance.is_finite() => Some(*distance),
+                _ => None,
+            }
+        });
+
+        expansions.push(Expansion {
+            center: center,
+            resources: group,
+
+            ground_distance: ground_distance,
+            is_start_location: is_start_location,
+            is_enemy_start_location: enemy_start.is_some(),
+        });
+    }
+
+    expansions.sort_by(|a, b| {
+        match (a.ground_distance, b.ground_distance) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    });
+
+    expansions
+}
+
+fn is_near(a: Point2, b: Point2) -> bool {
+    (a - b).norm() < START_LOCATION_DISTANCE
+}
+
+/// Group resources that are linked by a chain of nearby resources.
+fn group_resources(resources: Vec<Rc<Unit>>) -> Vec<Vec<Rc<Unit>>> {
+    // union-find over the resource indices
+    let mut parents: Vec<usize> = (0..resources.len()).collect();
+
+    fn find(parents: &mut Vec<usize>, i: usize) -> usize {
+        let mut root = i;
+
+        while parents[root] != root {
+            root = parents[root];
+        }
+
+        parents[i] = root;
+        root
+    }
+
+    for i in 0..resources.len() {
+        for j in i + 1..resources.len() {
+            let distance =
+                (resources[i].get_pos_2d() - resources[j].get_pos_2d()).norm();
+
+            if distance < RESOURCE_LINK_DISTANCE {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<Rc<Unit>>)> = vec![];
+
+    for (i, resource) in resources.into_iter().enumerate() {
+        let root = find(&mut parents, i);
+
+        match groups.iter().position(|&(r, _)| r == root) {
+            Some(g) => groups[g].1.push(resource),
+            None => groups.push((root, vec![resource])),
+        }
+    }
+
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Find the valid town hall placement closest to the resources.
+fn find_townhall_placement(
+    map_info: &MapInfo,
+    resources: &[Rc<Unit>],
+) -> Option<Point2> {
+    let sum = resources
+        .iter()
+        .fold(Point2::origin(), |sum, r| sum + r.get_pos_2d().coords);
+    let centroid = sum / resources.len() as f32;
+    let origin = to_tile(centroid);
+
+    let mut best: Option<(f32, Point2)> = None;
+
+    for y in -SEARCH_RADIUS..SEARCH_RADIUS + 1 {
+        for x in -SEARCH_RADIUS..SEARCH_RADIUS + 1 {
+            let tile = origin + Point2I::new(x, y);
+            let center = tile_center(tile);
+
+            if !is_clear_of_resources(center, resources)
+                || !is_placeable(map_info, tile)
+            {
+                continue;
+            }
+
+            let score = resources
+                .iter()
+                .map(|r| (r.get_pos_2d() - center).norm())
+                .sum::<f32>();
+
+            match best {
+                Some((best_score, _)) if best_score <= score => (),
+                _ => best = Some((score, center)),
+            }
+        }
+    }
+
+    best.map(|(_, center)| center)
+}
+
+fn is_clear_of_resources(center: Point2, resources: &[Rc<Unit>]) -> bool {
+    resources.iter().all(|r| {
+        let clearance = if r.get_unit_type().is_vespene_geyser() {
+            GEYSER_CLEARANCE
+        } else {
+            MINERAL_CLEARANCE
+        };
+
+        (r.get_pos_2d() - center).norm() >= clearance
+    })
+}
+
+/// Whether a 5x5 town hall centered on the tile can be placed.
+fn is_placeable(map_info: &MapInfo, tile: Point2I) -> bool {
+    for y in -TOWNHALL_HALF_SIZE..TOWNHALL_HALF_SIZE + 1 {
+        for x in -TOWNHALL_HALF_SIZE..TOWNHALL_HALF_SIZE + 1 {
+            let t = tile + Point2I::new(x, y);
+
+            if !map_info.is_placeable(tile_center(t)) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::raw;
+
+    use fixtures::{self, map_info, raw_unit, response};
+
+    fn resource(tag: u64, unit_type: u32, x: f32, y: f32) -> raw::Unit {
+        let mut unit = raw_unit(tag, unit_type, raw::Alliance::Neutral);
+
+        unit.set_display_type(raw::DisplayType::Snapshot);
+        unit.mut_pos().set_x(x);
+        unit.mut_pos().set_y(y);
+
+        unit
+    }
+
+    #[test]
+    fn test_find_expansions() {
+        let mut units = vec![];
+
+        // a row of minerals below a cliff, with a geyser that is only linked
+        // to the closest ones, and our command center below them
+        for x in 20..28 {
+            units.push(resource(x, 341, x as f32 + 0.5, 40.5));
+        }
+        units.push(resource(100, 342, 33.5, 40.5));
+
+        let mut cc = raw_unit(1, 18, raw::Alliance::Domestic);
+        cc.mut_pos().set_x(24.5);
+        cc.mut_pos().set_y(34.5);
+        units.push(cc);
+
+        // a column of minerals that can only be taken from the right
+        for y in 8..13 {
+            units.push(resource(200 + y, 341, 50.5, y as f32 + 0.5));
+        }
+
+        // too small to be a base
+        units.push(resource(300, 341, 10.5, 50.5));
+        units.push(resource(301, 341, 11.5, 50.5));
+
+        let map_info = map_info(64, 64, |x, y| {
+            (x >= 49 || y >= 24) && (x >= 40 || y < 44)
+        });
+        let observation = fixtures::observation(response(0, units, vec![]));
+
+        let expansions = find_expansions(&map_info, &observation);
+
+        assert_eq!(expansions.len(), 2);
+
+        let main = &expansions[0];
+
+        assert!(main.is_start_location());
+        assert!(!main.is_enemy_start_location());
+        assert_eq!(main.get_center(), Point2::new(24.5, 34.5));
+        assert_eq!(main.get_resources().len(), 9);
+
+        let other = &expansions[1];
+
+        assert!(!other.is_start_location());
+        assert_eq!(other.get_center(), Point2::new(56.5, 10.5));
+        assert_eq!(other.get_resources().len(), 5);
+        assert!(
+            other.get_ground_distance().unwrap()
+                > main.get_ground_distance().unwrap()
+        );
+    }
+}
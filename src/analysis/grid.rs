@@ -0,0 +1,104 @@
+use data::{MapInfo, Point2, Point2I};
+
+/// Tile that contains the given point.
+pub fn to_tile(point: Point2) -> Point2I {
+    Point2I::new(point.x.floor() as i32, point.y.floor() as i32)
+}
+
+/// Point in the center of the given tile.
+pub fn tile_center(tile: Point2I) -> Point2 {
+    Point2::new(tile.x as f32 + 0.5, tile.y as f32 + 0.5)
+}
+
+/// A value for each tile of the map.
+///
+/// Tiles are addressed in world space, so (0, 0) is the lower-left corner of
+/// the map.
+#[derive(Debug, Clone)]
+pub struct Grid<T> {
+    width: u32,
+    height: u32,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Create a grid with every tile set to the same value.
+    pub fn new(width: u32, height: u32, value: T) -> Self {
+        Self {
+            width: width,
+            height: height,
+            cells: vec![value; (width * height) as usize],
+        }
+    }
+
+    /// Create a grid the size of the map by sampling the center of each
+    /// tile.
+    pub fn from_map<F>(map_info: &MapInfo, sample: F) -> Self
+    where
+        F: Fn(Point2) -> T,
+    {
+        let (width, height) = map_info.get_dimensions();
+        let mut cells = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                cells.push(sample(tile_center(Point2I::new(x, y))));
+            }
+        }
+
+        Self {
+            width: width,
+            height: height,
+            cells: cells,
+        }
+    }
+
+    /// Width and height of the grid in tiles.
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Whether the tile is inside of the grid.
+    pub fn contains(&self, tile: Point2I) -> bool {
+        tile.x >= 0 && tile.y >= 0 && (tile.x as u32) < self.width
+            && (tile.y as u32) < self.height
+    }
+
+    /// Value of the given tile.
+    pub fn get(&self, tile: Point2I) -> Option<&T> {
+        self.index(tile).map(|i| &self.cells[i])
+    }
+
+    /// Mutable value of the given tile.
+    pub fn get_mut(&mut self, tile: Point2I) -> Option<&mut T> {
+        match self.index(tile) {
+            Some(i) => Some(&mut self.cells[i]),
+            None => None,
+        }
+    }
+
+    /// Set the value of the given tile (ignored if the tile is outside of the
+    /// grid).
+    pub fn set(&mut self, tile: Point2I, value: T) {
+        if let Some(cell) = self.get_mut(tile) {
+            *cell = value;
+        }
+    }
+
+    /// Every tile in the grid along with its value.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (Point2I, &'a T)> + 'a {
+        let width = self.width as i32;
+
+        self.cells.iter().enumerate().map(move |(i, value)| {
+            (Point2I::new(i as i32 % width, i as i32 / width), value)
+        })
+    }
+
+    fn index(&self, tile: Point2I) -> Option<usize> {
+        if self.contains(tile) {
+            Some((tile.y as u32 * self.width + tile.x as u32) as usize)
+        } else {
+            None
+        }
+    }
+}
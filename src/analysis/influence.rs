@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use analysis::grid::{tile_center, to_tile, Grid};
+use data::{
+    MapInfo,
+    Point2,
+    Point2I,
+    Unit,
+    UnitType,
+    UnitTypeData,
+    WeaponTargetType,
+};
+
+/// Extra distance around weapon ranges that is still considered dangerous
+/// (units need some time to turn around and get away).
+const THREAT_MARGIN: f32 = 1.0;
+
+/// A weighted value for each tile of the map.
+///
+/// Influence maps are filled by the bot (usually every step, from the enemy
+/// units) and can be used to find safe routes with
+/// Pathfinder::find_weighted_path or safe places to retreat to.
+#[derive(Debug, Clone)]
+pub struct InfluenceMap {
+    grid: Grid<f32>,
+}
+
+impl InfluenceMap {
+    /// Create an empty influence map the size of the map.
+    pub fn new(map_info: &MapInfo) -> Self {
+        let (width, height) = map_info.get_dimensions();
+
+        Self::from_grid(Grid::new(width, height, 0.0))
+    }
+
+    /// Create an influence map from existing values.
+    pub fn from_grid(grid: Grid<f32>) -> Self {
+        Self { grid: grid }
+    }
+
+    /// The influence of each tile.
+    pub fn get_grid(&self) -> &Grid<f32> {
+        &self.grid
+    }
+
+    /// Influence at the given point (zero outside of the map).
+    pub fn get(&self, point: Point2) -> f32 {
+        self.grid.get(to_tile(point)).map_or(0.0, |value| *value)
+    }
+
+    /// Reset every tile to zero.
+    pub fn clear(&mut self) {
+        let (width, height) = self.grid.get_dimensions();
+
+        self.grid = Grid::new(width, height, 0.0);
+    }
+
+    /// Add a value to every tile within the radius of the center.
+    pub fn add_circle(&mut self, center: Point2, radius: f32, value: f32) {
+        let origin = to_tile(center);
+        let reach = radius.ceil() as i32;
+
+        for y in -reach..reach + 1 {
+            for x in -reach..reach + 1 {
+                let tile = origin + Point2I::new(x, y);
+
+                if (tile_center(tile) - center).norm() > radius {
+                    continue;
+                }
+
+                if let Some(cell) = self.grid.get_mut(tile) {
+                    *cell += value;
+                }
+            }
+        }
+    }
+
+    /// Add the threat that a unit poses to units of the given target type.
+    ///
+    /// Every weapon that can hit the target type adds its damage per second
+    /// to the tiles within its range.
+    pub fn add_threat(
+        &mut self,
+        unit: &Unit,
+        data: &UnitTypeData,
+        target: WeaponTargetType,
+    ) {
+        for weapon in data.get_weapons() {
+            let can_hit = match (weapon.get_target_type(), target) {
+                (WeaponTargetType::Any, _) | (_, WeaponTargetType::Any) => true,
+                (weapon_target, target) => weapon_target == target,
+            };
+
+            if !can_hit || weapon.get_speed() <= 0.0 {
+                continue;
+            }
+
+            let dps = weapon.get_damage() * weapon.get_attacks() as f32
+                / weapon.get_speed();
+
+            self.add_circle(
+                unit.get_pos_2d(),
+                weapon.get_range() + unit.get_radius() + THREAT_MARGIN,
+                dps,
+            );
+        }
+    }
+
+    /// Add the threat of several units (ie. every visible enemy).
+    ///
+    /// Units without any game data are ignored.
+    pub fn add_threats(
+        &mut self,
+        units: &[Rc<Unit>],
+        unit_data: &HashMap<UnitType, UnitTypeData>,
+        target: WeaponTargetType,
+    ) {
+        for unit in units {
+            if let Some(data) = unit_data.get(&unit.get_unit_type()) {
+                self.add_threat(unit, data, target);
+            }
+        }
+    }
+
+    /// Point with the lowest influence within the radius of the center.
+    ///
+    /// Ties are broken by the distance to the center, so this returns the
+    /// center itself if there is no influence nearby.
+    pub fn get_safest_point(&self, center: Point2, radius: f32) -> Point2 {
+        let origin = to_tile(center);
+        let reach = radius.ceil() as i32;
+
+        let mut best = (self.get(center), 0.0, center);
+
+        for y in -reach..reach + 1 {
+            for x in -reach..reach + 1 {
+                let tile = origin + Point2I::new(x, y);
+                let point = tile_center(tile);
+                let distance = (point - center).norm();
+
+                if distance > radius || !self.grid.contains(tile) {
+                    continue;
+                }
+
+                let value = self.get(point);
+
+                if value < best.0 || (value == best.0 && distance < best.1) {
+                    best = (value, distance, point);
+                }
+            }
+        }
+
+        best.2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::{data, raw};
+
+    use fixtures::unit_at;
+    use FromProto;
+
+    /// Data of a unit with the given weapons (target, damage, attacks, range
+    /// and speed).
+    fn unit_data(
+        weapons: &[(data::Weapon_TargetType, f32, u32, f32, f32)],
+    ) -> UnitTypeData {
+        let mut data = data::UnitTypeData::new();
+        data.set_unit_id(48);
+
+        for &(target, damage, attacks, range, speed) in weapons {
+            let mut weapon = data::Weapon::new();
+
+            weapon.set_field_type(target);
+            weapon.set_damage(damage);
+            weapon.set_attacks(attacks);
+            weapon.set_range(range);
+            weapon.set_speed(speed);
+
+            data.mut_weapons().push(weapon);
+        }
+
+        UnitTypeData::from_proto(data).unwrap()
+    }
+
+    #[test]
+    fn test_add_threat() {
+        let unit = unit_at(1, 48, raw::Alliance::Enemy, 10.5, 10.5);
+        let data = unit_data(&[
+            // 10 dps with a range of 3
+            (data::Weapon_TargetType::Ground, 10.0, 2, 3.0, 2.0),
+            // 12 dps with a range of 5
+            (data::Weapon_TargetType::Air, 6.0, 1, 5.0, 0.5),
+            // not a real weapon
+            (data::Weapon_TargetType::Any, 10.0, 1, 5.0, 0.0),
+        ]);
+
+        let threat = |target| {
+            let mut influence =
+                InfluenceMap::from_grid(Grid::new(20, 20, 0.0));
+
+            influence.add_threat(&unit, &data, target);
+            influence
+        };
+
+        // ranges are extended by the margin
+        let ground = threat(WeaponTargetType::Ground);
+
+        assert_eq!(ground.get(Point2::new(10.5, 10.5)), 10.0);
+        assert_eq!(ground.get(Point2::new(14.5, 10.5)), 10.0);
+        assert_eq!(ground.get(Point2::new(15.5, 10.5)), 0.0);
+
+        let air = threat(WeaponTargetType::Air);
+
+        assert_eq!(air.get(Point2::new(10.5, 10.5)), 12.0);
+        assert_eq!(air.get(Point2::new(16.5, 10.5)), 12.0);
+        assert_eq!(air.get(Point2::new(17.5, 10.5)), 0.0);
+
+        // units that can be hit by anything are threatened by every weapon
+        let any = threat(WeaponTargetType::Any);
+
+        assert_eq!(any.get(Point2::new(10.5, 10.5)), 22.0);
+        assert_eq!(any.get(Point2::new(16.5, 10.5)), 12.0);
+    }
+
+    #[test]
+    fn test_safest_point() {
+        let center = Point2::new(2.5, 2.5);
+        let influence = |tiles: &[(i32, i32, f32)]| {
+            let mut grid = Grid::new(5, 5, 3.0);
+
+            for &(x, y, value) in tiles {
+                grid.set(Point2I::new(x, y), value);
+            }
+
+            InfluenceMap::from_grid(grid)
+        };
+
+        // the center itself (not the center of its tile) if it is safest
+        let empty = InfluenceMap::from_grid(Grid::new(5, 5, 0.0));
+
+        assert_eq!(
+            empty.get_safest_point(Point2::new(2.2, 2.7), 2.0),
+            Point2::new(2.2, 2.7)
+        );
+
+        // the lowest influence wins over the distance
+        assert_eq!(
+            influence(&[(3, 3, 2.0), (2, 4, 1.0)])
+                .get_safest_point(center, 2.0),
+            Point2::new(2.5, 4.5)
+        );
+
+        // ties go to the closest point, and tiles out of reach are ignored
+        assert_eq!(
+            influence(&[(2, 4, 1.0), (1, 2, 1.0), (0, 0, 0.0)])
+                .get_safest_point(center, 2.0),
+            Point2::new(1.5, 2.5)
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! Contains analysis of the map and game state that most bots need.
+//!
+//! None of these are part of the SC2 API, they are computed by the library
+//! from the MapInfo and Observations.
+
+mod combat;
+mod expansion;
+mod grid;
+mod influence;
+mod pathing;
+mod spatial;
+mod terrain;
+
+pub use self::combat::{
+    Army,
+    CombatOutcome,
+    CombatResult,
+    CombatSimulator,
+    CombatUnit,
+};
+pub use self::expansion::{find_expansions, Expansion};
+pub use self::grid::{tile_center, to_tile, Grid};
+pub use self::influence::InfluenceMap;
+pub use self::pathing::{ground_distances, Pathfinder};
+pub use self::spatial::{SpatialIndex, UnitFilter};
+pub use self::terrain::{Choke, Ramp, Region, TerrainAnalysis};
@@ -0,0 +1,432 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32;
+
+use analysis::grid::{tile_center, to_tile, Grid};
+use analysis::influence::InfluenceMap;
+use data::{MapInfo, Point2, Point2I};
+
+/// Tiles around the origin that are considered reachable even if they are
+/// blocked (ie. the starting town hall sits on top of the start location).
+const ORIGIN_RADIUS: i32 = 3;
+
+/// Ground distance from the given point to every tile on the map.
+///
+/// Unreachable tiles are set to infinity. Units can move diagonally, but they
+/// cannot cut the corners of blocked tiles.
+pub fn ground_distances(map_info: &MapInfo, from: Point2) -> Grid<f32> {
+    let pathable = Grid::from_map(map_info, |p| map_info.is_pathable(p));
+    let (width, height) = pathable.get_dimensions();
+
+    let mut distances = Grid::new(width, height, f32::INFINITY);
+    let mut queue = BinaryHeap::new();
+
+    let origin = to_tile(from);
+
+    for y in -ORIGIN_RADIUS..ORIGIN_RADIUS + 1 {
+        for x in -ORIGIN_RADIUS..ORIGIN_RADIUS + 1 {
+            let tile = origin + Point2I::new(x, y);
+
+            if pathable.contains(tile) {
+                let cost = (tile_center(tile) - from).norm();
+
+                distances.set(tile, cost);
+                queue.push(Node {
+                    cost: cost,
+                    tile: tile,
+                });
+            }
+        }
+    }
+
+    while let Some(Node { cost, tile }) = queue.pop() {
+        if cost > *distances.get(tile).unwrap() {
+            continue;
+        }
+
+        let is_open = |t: Point2I| *pathable.get(t).unwrap_or(&false);
+
+        for (next, step) in neighbors(is_open, tile) {
+            let next_cost = cost + step;
+
+            if next_cost < *distances.get(next).unwrap() {
+                distances.set(next, next_cost);
+                queue.push(Node {
+                    cost: next_cost,
+                    tile: next,
+                });
+            }
+        }
+    }
+
+    distances
+}
+
+/// Finds routes for ground units.
+///
+/// The server can only tell us how far apart two points are, but kiting and
+/// harassment need to know the actual route. This is a plain A* over the
+/// tiles of the pathing grid (jump point search would be faster, but it
+/// cannot handle the weighted tiles of an influence map).
+#[derive(Debug, Clone)]
+pub struct Pathfinder {
+    pathable: Grid<bool>,
+    clearances: Grid<f32>,
+}
+
+impl Pathfinder {
+    /// Create a pathfinder for the map.
+    ///
+    /// Like the rest of the terrain analysis, this uses the pathing grid
+    /// from the start of the game, so structures built since then are not
+    /// taken into account.
+    pub fn new(map_info: &MapInfo) -> Self {
+        Self::from_grid(Grid::from_map(map_info, |p| map_info.is_pathable(p)))
+    }
+
+    fn from_grid(pathable: Grid<bool>) -> Self {
+        Self {
+            clearances: clearances(&pathable),
+            pathable: pathable,
+        }
+    }
+
+    /// Distance from the given point to the closest unpathable tile.
+    pub fn get_clearance(&self, point: Point2) -> f32 {
+        self.clearances.get(to_tile(point)).map_or(0.0, |c| *c)
+    }
+
+    /// Whether a unit with the given radius fits on the given point.
+    pub fn is_walkable(&self, point: Point2, radius: f32) -> bool {
+        self.fits(to_tile(point), radius)
+    }
+
+    /// Find the shortest route for a unit with the given radius.
+    ///
+    /// The route is a list of waypoints where the path changes direction,
+    /// ending at the destination (the starting point is not included).
+    /// Returns None if the destination cannot be reached.
+    pub fn find_path(
+        &self,
+        from: Point2,
+        to: Point2,
+        radius: f32,
+    ) -> Option<Vec<Point2>> {
+        self.search(from, to, radius, None)
+    }
+
+    /// Find a route that avoids the influence of the given map.
+    ///
+    /// Moving through a tile costs (1 + influence) times as much as usual,
+    /// so the path will take detours around tiles with a lot of influence
+    /// (ie. threat) if there is a reasonable way around them.
+    pub fn find_weighted_path(
+        &self,
+        from: Point2,
+        to: Point2,
+        radius: f32,
+        influence: &InfluenceMap,
+    ) -> Option<Vec<Point2>> {
+        self.search(from, to, radius, Some(influence))
+    }
+
+    fn search(
+        &self,
+        from: Point2,
+        to: Point2,
+        radius: f32,
+        influence: Option<&InfluenceMap>,
+    ) -> Option<Vec<Point2>> {
+        let start = to_tile(from);
+        let goal = to_tile(to);
+
+        if !self.pathable.contains(start) || !self.fits(goal, radius) {
+            return None;
+        }
+
+        let (width, height) = self.pathable.get_dimensions();
+
+        let mut costs = Grid::new(width, height, f32::INFINITY);
+        let mut parents = Grid::new(width, height, None);
+        let mut closed = Grid::new(width, height, false);
+        let mut open = BinaryHeap::new();
+
+        costs.set(start, 0.0);
+        open.push(Node {
+            cost: estimate(start, goal),
+            tile: start,
+        });
+
+        // the unit might be standing too close to a wall, but it should
+        // still be able to walk away from it.
+        let is_open = |t: Point2I| t == start || self.fits(t, radius);
+
+        while let Some(Node { tile, .. }) = open.pop() {
+            if tile == goal {
+                return Some(waypoints(&parents, goal, to));
+            }
+            if *closed.get(tile).unwrap() {
+                continue;
+            }
+
+            closed.set(tile, true);
+
+            let cost = *costs.get(tile).unwrap();
+
+            for (next, step) in neighbors(&is_open, tile) {
+                let weight = match influence {
+                    Some(influence) => {
+                        1.0 + influence.get(tile_center(next)).max(0.0)
+                    },
+                    None => 1.0,
+                };
+                let next_cost = cost + step * weight;
+
+                if next_cost < *costs.get(next).unwrap() {
+                    costs.set(next, next_cost);
+                    parents.set(next, Some(tile));
+                    open.push(Node {
+                        cost: next_cost + estimate(next, goal),
+                        tile: next,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn fits(&self, tile: Point2I, radius: f32) -> bool {
+        // clearance is measured from the center of the tile to the center of
+        // the closest blocked tile
+        self.clearances.get(tile).map_or(false, |c| *c >= radius + 0.5)
+    }
+}
+
+/// Octile distance between two tiles (the cost of the path if there were no
+/// obstacles).
+fn estimate(from: Point2I, to: Point2I) -> f32 {
+    let dx = (to.x - from.x).abs() as f32;
+    let dy = (to.y - from.y).abs() as f32;
+
+    dx + dy + (f32::consts::SQRT_2 - 2.0) * dx.min(dy)
+}
+
+/// Follow the parents back from the goal and keep the tiles where the path
+/// turns.
+fn waypoints(
+    parents: &Grid<Option<Point2I>>,
+    goal: Point2I,
+    to: Point2,
+) -> Vec<Point2> {
+    let mut tiles = vec![goal];
+
+    while let Some(&Some(parent)) = parents.get(*tiles.last().unwrap()) {
+        tiles.push(parent);
+    }
+
+    tiles.reverse();
+
+    let mut waypoints = vec![];
+
+    for i in 1..tiles.len().saturating_sub(1) {
+        if tiles[i] - tiles[i - 1] != tiles[i + 1] - tiles[i] {
+            waypoints.push(tile_center(tiles[i]));
+        }
+    }
+
+    if tiles.len() > 1 {
+        waypoints.push(to);
+    }
+
+    waypoints
+}
+
+/// Distance from every tile to the closest tile that ground units cannot
+/// move through.
+///
+/// The edges of the map count as blocked, and blocked tiles have a clearance
+/// of zero.
+pub fn clearances(pathable: &Grid<bool>) -> Grid<f32> {
+    let (width, height) = pathable.get_dimensions();
+    let mut clearances = Grid::new(width, height, 0.0);
+
+    for (tile, open) in pathable.iter() {
+        if *open {
+            clearances.set(tile, f32::INFINITY);
+        }
+    }
+
+    // two-pass chamfer distance transform
+    let forward = [
+        (-1, 0, 1.0),
+        (-1, -1, f32::consts::SQRT_2),
+        (0, -1, 1.0),
+        (1, -1, f32::consts::SQRT_2),
+    ];
+    let backward = [
+        (1, 0, 1.0),
+        (1, 1, f32::consts::SQRT_2),
+        (0, 1, 1.0),
+        (-1, 1, f32::consts::SQRT_2),
+    ];
+
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            relax(&mut clearances, Point2I::new(x, y), &forward);
+        }
+    }
+    for y in (0..height as i32).rev() {
+        for x in (0..width as i32).rev() {
+            relax(&mut clearances, Point2I::new(x, y), &backward);
+        }
+    }
+
+    clearances
+}
+
+fn relax(
+    clearances: &mut Grid<f32>,
+    tile: Point2I,
+    offsets: &[(i32, i32, f32)],
+) {
+    let mut clearance = *clearances.get(tile).unwrap();
+
+    if clearance == 0.0 {
+        return;
+    }
+
+    for &(x, y, cost) in offsets {
+        // anything outside of the map is blocked
+        let neighbor = clearances
+            .get(tile + Point2I::new(x, y))
+            .map_or(0.0, |c| *c);
+
+        clearance = clearance.min(neighbor + cost);
+    }
+
+    clearances.set(tile, clearance);
+}
+
+/// Open neighbors of a tile along with the cost of moving to them.
+fn neighbors<F>(is_open: F, tile: Point2I) -> Vec<(Point2I, f32)>
+where
+    F: Fn(Point2I) -> bool,
+{
+    let mut neighbors = Vec::with_capacity(8);
+
+    for y in -1..2 {
+        for x in -1..2 {
+            if x == 0 && y == 0 {
+                continue;
+            }
+
+            let next = tile + Point2I::new(x, y);
+
+            if !is_open(next) {
+                continue;
+            }
+
+            if x != 0 && y != 0 {
+                // no cutting corners
+                if !is_open(tile + Point2I::new(x, 0))
+                    || !is_open(tile + Point2I::new(0, y))
+                {
+                    continue;
+                }
+
+                neighbors.push((next, f32::consts::SQRT_2));
+            } else {
+                neighbors.push((next, 1.0));
+            }
+        }
+    }
+
+    neighbors
+}
+
+/// Entry in the open set, ordered so that the lowest cost is popped first.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Node {
+    cost: f32,
+    tile: Point2I,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 20x20 room with a wall down the middle that has a 2 tile wide gap at
+    /// the top and a 6 tile wide gap at the bottom.
+    fn room() -> Grid<bool> {
+        let mut pathable = Grid::new(20, 20, true);
+
+        for y in 6..16 {
+            pathable.set(Point2I::new(10, y), false);
+        }
+        for y in 18..20 {
+            pathable.set(Point2I::new(10, y), false);
+        }
+
+        pathable
+    }
+
+    #[test]
+    fn test_find_path() {
+        let pathfinder = Pathfinder::from_grid(room());
+
+        let from = Point2::new(5.5, 16.5);
+        let to = Point2::new(15.5, 16.5);
+
+        let path = pathfinder.find_path(from, to, 0.5).unwrap();
+
+        assert_eq!(*path.last().unwrap(), to);
+        assert!(path.iter().all(|p| pathfinder.is_walkable(*p, 0.5)));
+
+        // too big for the top gap, so it has to go around the bottom
+        let path = pathfinder.find_path(from, to, 1.5).unwrap();
+
+        assert!(path.iter().any(|p| p.y < 6.0));
+
+        // nothing fits through either gap
+        assert!(pathfinder.find_path(from, to, 3.0).is_none());
+    }
+
+    #[test]
+    fn test_find_weighted_path() {
+        let pathfinder = Pathfinder::from_grid(room());
+        let mut influence = InfluenceMap::from_grid(Grid::new(20, 20, 0.0));
+
+        // something dangerous is guarding the top gap
+        influence.add_circle(Point2::new(10.5, 16.5), 3.0, 100.0);
+
+        let path = pathfinder
+            .find_weighted_path(
+                Point2::new(5.5, 16.5),
+                Point2::new(15.5, 16.5),
+                0.5,
+                &influence,
+            )
+            .unwrap();
+
+        assert!(path.iter().all(|p| influence.get(*p) == 0.0));
+        assert!(path.iter().any(|p| p.y < 6.0));
+    }
+}
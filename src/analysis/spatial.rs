@@ -0,0 +1,316 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use data::{
+    Alliance,
+    Attribute,
+    Point2,
+    Rect2,
+    Unit,
+    UnitType,
+    UnitTypeData,
+    Vector2,
+};
+
+/// Width and height of each cell of the index.
+const CELL_SIZE: f32 = 8.0;
+
+/// Restricts which units are returned by a spatial query.
+///
+/// An empty filter matches every unit.
+#[derive(Debug, Clone, Default)]
+pub struct UnitFilter {
+    alliance: Option<Alliance>,
+    unit_types: Vec<UnitType>,
+    attribute_types: Option<HashSet<UnitType>>,
+}
+
+impl UnitFilter {
+    /// Create a filter that matches every unit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match units with the given alliance.
+    pub fn alliance(self, alliance: Alliance) -> Self {
+        Self {
+            alliance: Some(alliance),
+            ..self
+        }
+    }
+
+    /// Only match units of the given type.
+    ///
+    /// This can be called multiple times to match any of several types.
+    pub fn unit_type(mut self, unit_type: UnitType) -> Self {
+        self.unit_types.push(unit_type);
+
+        self
+    }
+
+    /// Only match units that have the given attribute.
+    ///
+    /// Units do not know their own attributes, so this needs the unit data
+    /// (see ObserverClient::get_unit_data). This can be called multiple
+    /// times to require several attributes.
+    pub fn attribute(
+        self,
+        attribute: Attribute,
+        unit_data: &HashMap<UnitType, UnitTypeData>,
+    ) -> Self {
+        let types = unit_data
+            .values()
+            .filter(|data| data.get_attributes().contains(&attribute))
+            .map(|data| data.get_id())
+            .collect::<HashSet<_>>();
+
+        Self {
+            attribute_types: Some(match self.attribute_types {
+                Some(previous) => {
+                    previous.intersection(&types).map(|t| *t).collect()
+                },
+                None => types,
+            }),
+            ..self
+        }
+    }
+
+    /// Whether the unit passes the filter.
+    pub fn matches(&self, unit: &Unit) -> bool {
+        let unit_type = unit.get_unit_type();
+
+        self.alliance.map_or(true, |a| a == unit.get_alliance())
+            && (self.unit_types.is_empty()
+                || self.unit_types.contains(&unit_type))
+            && self.attribute_types
+                .as_ref()
+                .map_or(true, |types| types.contains(&unit_type))
+    }
+}
+
+/// Buckets units by position so that they can be queried without looking at
+/// every unit.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    units: Vec<Rc<Unit>>,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialIndex {
+    /// Index the given units.
+    pub fn new(units: &[Rc<Unit>]) -> Self {
+        let mut cells = HashMap::new();
+
+        for (i, unit) in units.iter().enumerate() {
+            cells
+                .entry(to_cell(unit.get_pos_2d()))
+                .or_insert_with(|| vec![])
+                .push(i);
+        }
+
+        Self {
+            units: units.to_vec(),
+            cells: cells,
+        }
+    }
+
+    /// Units within the radius of the center (measured from the position of
+    /// each unit, so their own radius is not included).
+    pub fn units_in_circle(
+        &self,
+        center: Point2,
+        radius: f32,
+        filter: &UnitFilter,
+    ) -> Vec<Rc<Unit>> {
+        let offset = Vector2::new(radius, radius);
+
+        self.query(center - offset, center + offset, |u| {
+            (u.get_pos_2d() - center).norm() <= radius && filter.matches(u)
+        })
+    }
+
+    /// Units within the given rectangle.
+    pub fn units_in_rect(
+        &self,
+        rect: Rect2,
+        filter: &UnitFilter,
+    ) -> Vec<Rc<Unit>> {
+        let (from, to) = (rect.from, rect.to);
+
+        let min = Point2::new(from.x.min(to.x), from.y.min(to.y));
+        let max = Point2::new(from.x.max(to.x), from.y.max(to.y));
+
+        self.query(min, max, |u| {
+            let pos = u.get_pos_2d();
+
+            pos.x >= min.x && pos.x <= max.x && pos.y >= min.y
+                && pos.y <= max.y && filter.matches(u)
+        })
+    }
+
+    /// Up to n units that are closest to the point, from the closest to the
+    /// farthest.
+    pub fn nearest_n(
+        &self,
+        point: Point2,
+        n: usize,
+        filter: &UnitFilter,
+    ) -> Vec<Rc<Unit>> {
+        if n == 0 || self.cells.is_empty() {
+            return vec![];
+        }
+
+        // the farthest ring that can still contain any units
+        let origin = to_cell(point);
+        let max_ring = self.cells
+            .keys()
+            .map(|&(x, y)| (x - origin.0).abs().max((y - origin.1).abs()))
+            .max()
+            .unwrap_or(0);
+
+        let mut found: Vec<(f32, usize)> = vec![];
+
+        for ring in 0..max_ring + 1 {
+            for cell in ring_cells(origin, ring) {
+                for &i in self.cells.get(&cell).map_or(&[][..], |c| &c[..]) {
+                    let unit = &self.units[i];
+
+                    if filter.matches(unit) {
+                        found.push(((unit.get_pos_2d() - point).norm(), i));
+                    }
+                }
+            }
+
+            found.sort_by(|a, b| {
+                a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal)
+            });
+            found.truncate(n);
+
+            // everything in the next ring is at least this far away
+            let reach = ring as f32 * CELL_SIZE;
+
+            if found.len() == n && found[n - 1].0 <= reach {
+                break;
+            }
+        }
+
+        found
+            .into_iter()
+            .map(|(_, i)| Rc::clone(&self.units[i]))
+            .collect()
+    }
+
+    /// Units in the cells that overlap the bounds that pass the test.
+    fn query<F>(&self, min: Point2, max: Point2, test: F) -> Vec<Rc<Unit>>
+    where
+        F: Fn(&Unit) -> bool,
+    {
+        let (from, to) = (to_cell(min), to_cell(max));
+        let mut units = vec![];
+
+        for y in from.1..to.1 + 1 {
+            for x in from.0..to.0 + 1 {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    for &i in cell {
+                        if test(&self.units[i]) {
+                            units.push(Rc::clone(&self.units[i]));
+                        }
+                    }
+                }
+            }
+        }
+
+        units
+    }
+}
+
+fn to_cell(point: Point2) -> (i32, i32) {
+    (
+        (point.x / CELL_SIZE).floor() as i32,
+        (point.y / CELL_SIZE).floor() as i32,
+    )
+}
+
+/// Cells that are exactly `ring` cells away from the origin.
+fn ring_cells(origin: (i32, i32), ring: i32) -> Vec<(i32, i32)> {
+    if ring == 0 {
+        return vec![origin];
+    }
+
+    let mut cells = vec![];
+
+    for i in -ring..ring + 1 {
+        cells.push((origin.0 + i, origin.1 - ring));
+        cells.push((origin.0 + i, origin.1 + ring));
+    }
+    for i in -ring + 1..ring {
+        cells.push((origin.0 - ring, origin.1 + i));
+        cells.push((origin.0 + ring, origin.1 + i));
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::raw;
+
+    use fixtures::unit_at;
+
+    fn unit(tag: u64, alliance: raw::Alliance, x: f32, y: f32) -> Rc<Unit> {
+        unit_at(tag, 48, alliance, x, y)
+    }
+
+    fn tags(units: Vec<Rc<Unit>>) -> Vec<u64> {
+        let mut tags = units.iter().map(|u| u.get_tag()).collect::<Vec<_>>();
+
+        tags.sort();
+        tags
+    }
+
+    #[test]
+    fn test_queries() {
+        let index = SpatialIndex::new(&[
+            unit(1, raw::Alliance::Domestic, 10.0, 10.0),
+            unit(2, raw::Alliance::Enemy, 12.0, 10.0),
+            unit(3, raw::Alliance::Enemy, 30.0, 10.0),
+            unit(4, raw::Alliance::Enemy, 50.0, 50.0),
+        ]);
+
+        let enemies = UnitFilter::new().alliance(Alliance::Enemy);
+
+        assert_eq!(
+            tags(index.units_in_circle(
+                Point2::new(10.0, 10.0),
+                5.0,
+                &UnitFilter::new()
+            )),
+            vec![1, 2]
+        );
+        assert_eq!(
+            tags(index.units_in_rect(
+                Rect2 {
+                    from: Point2::new(40.0, 0.0),
+                    to: Point2::new(0.0, 20.0),
+                },
+                &enemies
+            )),
+            vec![2, 3]
+        );
+        assert_eq!(
+            index
+                .nearest_n(Point2::new(0.0, 0.0), 2, &enemies)
+                .iter()
+                .map(|u| u.get_tag())
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(
+            index.nearest_n(Point2::new(0.0, 0.0), 10, &enemies).len(),
+            3
+        );
+    }
+}
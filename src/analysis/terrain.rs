@@ -0,0 +1,640 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f32;
+
+use analysis::grid::{tile_center, to_tile, Grid};
+use analysis::pathing::clearances;
+use data::{MapInfo, Point2, Point2I};
+
+/// Unbuildable slopes need at least this difference in height between their
+/// two sides to count as a ramp.
+const MIN_RAMP_HEIGHT: f32 = 1.0;
+/// Smaller patches of unbuildable ground are ignored.
+const MIN_RAMP_SIZE: usize = 4;
+/// Passages with at least this much clearance (half of their width) are
+/// never considered chokes.
+const MAX_CHOKE_CLEARANCE: f32 = 8.0;
+/// Regions are merged if the passage between them is nearly as open as the
+/// smaller region (ie. bumps in the walls do not create new regions).
+const MERGE_RATIO: f32 = 0.8;
+
+/// An open area of the map bounded by cliffs, ramps, and chokes.
+#[derive(Debug, Clone)]
+pub struct Region {
+    id: usize,
+    area: u32,
+    center: Point2,
+    height: f32,
+    neighbors: Vec<usize>,
+}
+
+impl Region {
+    /// Index of this region in TerrainAnalysis::get_regions.
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+    /// Number of tiles in this region.
+    pub fn get_area(&self) -> u32 {
+        self.area
+    }
+    /// The most open point of the region (farthest away from any wall).
+    pub fn get_center(&self) -> Point2 {
+        self.center
+    }
+    /// Average terrain height of the region.
+    pub fn get_height(&self) -> f32 {
+        self.height
+    }
+    /// Regions that are directly connected to this one by a ramp or choke.
+    pub fn get_neighbors(&self) -> &[usize] {
+        &self.neighbors
+    }
+}
+
+/// A pathable, unbuildable slope between two levels of the map.
+#[derive(Debug, Clone)]
+pub struct Ramp {
+    tiles: Vec<Point2I>,
+    center: Point2,
+    top_center: Point2,
+    bottom_center: Point2,
+    upper_region: Option<usize>,
+    lower_region: Option<usize>,
+}
+
+impl Ramp {
+    /// Tiles that make up the ramp.
+    pub fn get_tiles(&self) -> &[Point2I] {
+        &self.tiles
+    }
+    /// Middle of the ramp.
+    pub fn get_center(&self) -> Point2 {
+        self.center
+    }
+    /// Middle of the edge of the ramp that touches the upper region.
+    pub fn get_top_center(&self) -> Point2 {
+        self.top_center
+    }
+    /// Middle of the edge of the ramp that touches the lower region.
+    pub fn get_bottom_center(&self) -> Point2 {
+        self.bottom_center
+    }
+    /// Region at the top of the ramp.
+    pub fn get_upper_region(&self) -> Option<usize> {
+        self.upper_region
+    }
+    /// Region at the bottom of the ramp.
+    pub fn get_lower_region(&self) -> Option<usize> {
+        self.lower_region
+    }
+}
+
+/// A narrow passage between two regions on the same level.
+#[derive(Debug, Clone)]
+pub struct Choke {
+    center: Point2,
+    width: f32,
+    regions: (usize, usize),
+}
+
+impl Choke {
+    /// Narrowest point of the passage.
+    pub fn get_center(&self) -> Point2 {
+        self.center
+    }
+    /// Approximate width of the passage at its narrowest point.
+    pub fn get_width(&self) -> f32 {
+        self.width
+    }
+    /// The two regions that the choke connects.
+    pub fn get_regions(&self) -> (usize, usize) {
+        self.regions
+    }
+}
+
+/// Decomposition of the map into regions that are connected by ramps and
+/// chokes.
+#[derive(Debug, Clone)]
+pub struct TerrainAnalysis {
+    regions: Vec<Region>,
+    ramps: Vec<Ramp>,
+    chokes: Vec<Choke>,
+
+    region_map: Grid<Option<usize>>,
+}
+
+impl TerrainAnalysis {
+    /// Analyze the terrain of the map.
+    ///
+    /// This is fairly expensive and the terrain never changes, so it should
+    /// be done once at the start of the game. Keep in mind that the pathing
+    /// grid includes the resources, so mineral lines are treated like walls.
+    pub fn new(map_info: &MapInfo) -> Self {
+        Self::from_grids(
+            &Grid::from_map(map_info, |p| map_info.is_pathable(p)),
+            &Grid::from_map(map_info, |p| map_info.is_placeable(p)),
+            &Grid::from_map(map_info, |p| {
+                map_info.get_terrain_height_at(p).unwrap_or(0.0)
+            }),
+        )
+    }
+
+    fn from_grids(
+        pathable: &Grid<bool>,
+        placeable: &Grid<bool>,
+        heights: &Grid<f32>,
+    ) -> Self {
+        let ramp_tiles = find_ramps(pathable, placeable, heights);
+
+        // ramps are what separates the levels, so they are not part of any
+        // region.
+        let mut open = pathable.clone();
+
+        for tile in ramp_tiles.iter().flat_map(|tiles| tiles.iter()) {
+            open.set(*tile, false);
+        }
+
+        let (region_map, centers, boundaries) =
+            find_regions(&open, &clearances(pathable));
+
+        let mut regions = centers
+            .into_iter()
+            .enumerate()
+            .map(|(id, center)| Region {
+                id: id,
+                area: 0,
+                center: tile_center(center),
+                height: 0.0,
+                neighbors: vec![],
+            })
+            .collect::<Vec<_>>();
+
+        for (tile, region) in region_map.iter() {
+            if let Some(region) = *region {
+                regions[region].area += 1;
+                regions[region].height += *heights.get(tile).unwrap();
+            }
+        }
+        for region in &mut regions {
+            region.height /= region.area as f32;
+        }
+
+        let ramps = ramp_tiles
+            .into_iter()
+            .map(|tiles| create_ramp(tiles, &region_map, &regions, heights))
+            .collect::<Vec<_>>();
+
+        let mut chokes: Vec<Choke> = vec![];
+
+        // boundaries are sorted from the widest to the narrowest, so the
+        // first boundary of each choke is its center.
+        for (pair, tile, clearance) in boundaries {
+            let center = tile_center(tile);
+
+            let is_known = chokes.iter().any(|choke| {
+                choke.regions == pair
+                    && (choke.center - center).norm() <= choke.width + 1.0
+            });
+
+            if !is_known {
+                chokes.push(Choke {
+                    center: center,
+                    width: 2.0 * clearance,
+                    regions: pair,
+                });
+            }
+        }
+
+        let connections = chokes
+            .iter()
+            .map(|choke| choke.regions)
+            .chain(ramps.iter().filter_map(|ramp| {
+                match (ramp.upper_region, ramp.lower_region) {
+                    (Some(upper), Some(lower)) => Some((upper, lower)),
+                    _ => None,
+                }
+            }))
+            .collect::<Vec<_>>();
+
+        for (a, b) in connections {
+            regions[a].neighbors.push(b);
+            regions[b].neighbors.push(a);
+        }
+        for region in &mut regions {
+            region.neighbors.sort();
+            region.neighbors.dedup();
+        }
+
+        Self {
+            regions: regions,
+            ramps: ramps,
+            chokes: chokes,
+
+            region_map: region_map,
+        }
+    }
+
+    /// Every region on the map, from the most open to the least open.
+    pub fn get_regions(&self) -> &[Region] {
+        &self.regions
+    }
+    /// Every ramp on the map.
+    pub fn get_ramps(&self) -> &[Ramp] {
+        &self.ramps
+    }
+    /// Every choke on the map.
+    pub fn get_chokes(&self) -> &[Choke] {
+        &self.chokes
+    }
+
+    /// Region with the given id.
+    pub fn get_region(&self, id: usize) -> Option<&Region> {
+        self.regions.get(id)
+    }
+    /// Region that contains the given point.
+    ///
+    /// Returns None for unpathable points and for points on a ramp.
+    pub fn get_region_at(&self, point: Point2) -> Option<&Region> {
+        match self.region_map.get(to_tile(point)) {
+            Some(&Some(id)) => self.regions.get(id),
+            _ => None,
+        }
+    }
+    /// Ramps that touch the given region.
+    pub fn get_ramps_of(&self, region: usize) -> Vec<&Ramp> {
+        self.ramps
+            .iter()
+            .filter(|ramp| {
+                ramp.upper_region == Some(region)
+                    || ramp.lower_region == Some(region)
+            })
+            .collect()
+    }
+    /// Chokes that touch the given region.
+    pub fn get_chokes_of(&self, region: usize) -> Vec<&Choke> {
+        self.chokes
+            .iter()
+            .filter(|choke| {
+                choke.regions.0 == region || choke.regions.1 == region
+            })
+            .collect()
+    }
+
+    /// Whether ground units can move from one region to the other.
+    ///
+    /// Returns false if either id is not a region of the map.
+    pub fn is_connected(&self, from: usize, to: usize) -> bool {
+        if from >= self.regions.len() || to >= self.regions.len() {
+            return false;
+        }
+
+        let mut visited = HashSet::new();
+        let mut pending = VecDeque::new();
+
+        visited.insert(from);
+        pending.push_back(from);
+
+        while let Some(region) = pending.pop_front() {
+            if region == to {
+                return true;
+            }
+
+            for neighbor in self.regions[region].get_neighbors() {
+                if visited.insert(*neighbor) {
+                    pending.push_back(*neighbor);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Boundary between two regions that are too narrow to merge.
+type Boundary = ((usize, usize), Point2I, f32);
+
+/// Find the patches of unbuildable ground that connect two levels.
+fn find_ramps(
+    pathable: &Grid<bool>,
+    placeable: &Grid<bool>,
+    heights: &Grid<f32>,
+) -> Vec<Vec<Point2I>> {
+    let (width, height) = pathable.get_dimensions();
+
+    let is_ground = |t: Point2I| *pathable.get(t).unwrap_or(&false);
+    let is_slope =
+        |t: Point2I| is_ground(t) && !*placeable.get(t).unwrap_or(&true);
+
+    let mut visited = Grid::new(width, height, false);
+    let mut ramps = vec![];
+
+    for (tile, _) in pathable.iter() {
+        if *visited.get(tile).unwrap() || !is_slope(tile) {
+            continue;
+        }
+
+        let mut patch = vec![];
+        let mut pending = vec![tile];
+
+        visited.set(tile, true);
+
+        while let Some(next) = pending.pop() {
+            patch.push(next);
+
+            for neighbor in adjacent(next) {
+                if is_slope(neighbor) && !*visited.get(neighbor).unwrap() {
+                    visited.set(neighbor, true);
+                    pending.push(neighbor);
+                }
+            }
+        }
+
+        // compare the heights of the buildable ground around the patch
+        let (low, high) = patch
+            .iter()
+            .flat_map(|t| adjacent(*t))
+            .filter(|t| is_ground(*t) && !is_slope(*t))
+            .map(|t| *heights.get(t).unwrap())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), h| {
+                (low.min(h), high.max(h))
+            });
+
+        if patch.len() >= MIN_RAMP_SIZE && high - low >= MIN_RAMP_HEIGHT {
+            ramps.push(patch);
+        }
+    }
+
+    ramps
+}
+
+/// Split the open tiles into regions.
+///
+/// This is a watershed over the clearance of each tile. Tiles are flooded
+/// from the most open to the least open, and whenever two regions meet at a
+/// point that is much narrower than either of them, that point becomes a
+/// boundary (and possibly a choke) instead of merging the regions.
+///
+/// Returns the region of each tile, the center of each region, and the
+/// boundaries between regions.
+fn find_regions(
+    open: &Grid<bool>,
+    clearances: &Grid<f32>,
+) -> (Grid<Option<usize>>, Vec<Point2I>, Vec<Boundary>) {
+    let (width, height) = open.get_dimensions();
+
+    let mut tiles = open
+        .iter()
+        .filter(|&(_, open)| *open)
+        .map(|(tile, _)| (tile, *clearances.get(tile).unwrap()))
+        .collect::<Vec<_>>();
+
+    tiles.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+    // union-find over the basins
+    let mut parents: Vec<usize> = vec![];
+    let mut peaks: Vec<(Point2I, f32)> = vec![];
+
+    fn find(parents: &mut Vec<usize>, i: usize) -> usize {
+        let mut root = i;
+
+        while parents[root] != root {
+            root = parents[root];
+        }
+
+        parents[i] = root;
+        root
+    }
+
+    let mut labels = Grid::new(width, height, None);
+    let mut boundaries = vec![];
+
+    for (tile, clearance) in tiles {
+        let mut basins: Vec<usize> = vec![];
+
+        for neighbor in adjacent(tile) {
+            if let Some(&Some(label)) = labels.get(neighbor) {
+                let basin = find(&mut parents, label);
+
+                if !basins.contains(&basin) {
+                    basins.push(basin);
+                }
+            }
+        }
+
+        basins.sort_by(|a, b| {
+            peaks[*b].1.partial_cmp(&peaks[*a].1).unwrap_or(Ordering::Equal)
+        });
+
+        let label = match basins.first() {
+            Some(&basin) => {
+                for &other in &basins[1..] {
+                    if clearance >= MAX_CHOKE_CLEARANCE
+                        || clearance >= MERGE_RATIO * peaks[other].1
+                    {
+                        parents[other] = basin;
+                    } else {
+                        boundaries.push(((basin, other), tile, clearance));
+                    }
+                }
+
+                basin
+            },
+            None => {
+                parents.push(peaks.len());
+                peaks.push((tile, clearance));
+
+                peaks.len() - 1
+            },
+        };
+
+        labels.set(tile, Some(label));
+    }
+
+    // number the remaining basins in the order they were found
+    let mut ids = HashMap::new();
+    let mut centers = vec![];
+
+    for label in 0..parents.len() {
+        if find(&mut parents, label) == label {
+            ids.insert(label, centers.len());
+            centers.push(peaks[label].0);
+        }
+    }
+
+    let mut region_map = Grid::new(width, height, None);
+
+    for (tile, label) in labels.iter() {
+        if let Some(label) = *label {
+            region_map.set(tile, Some(ids[&find(&mut parents, label)]));
+        }
+    }
+
+    let boundaries = boundaries
+        .into_iter()
+        .filter_map(|((a, b), tile, clearance)| {
+            let a = ids[&find(&mut parents, a)];
+            let b = ids[&find(&mut parents, b)];
+
+            match a.cmp(&b) {
+                Ordering::Less => Some(((a, b), tile, clearance)),
+                Ordering::Greater => Some(((b, a), tile, clearance)),
+                Ordering::Equal => None,
+            }
+        })
+        .collect();
+
+    (region_map, centers, boundaries)
+}
+
+fn create_ramp(
+    tiles: Vec<Point2I>,
+    region_map: &Grid<Option<usize>>,
+    regions: &[Region],
+    heights: &Grid<f32>,
+) -> Ramp {
+    let center = centroid(&tiles);
+    let height = tiles
+        .iter()
+        .map(|t| *heights.get(*t).unwrap())
+        .sum::<f32>() / tiles.len() as f32;
+
+    // ramp tiles that touch each region
+    let mut edges: HashMap<usize, Vec<Point2I>> = HashMap::new();
+
+    for tile in &tiles {
+        for neighbor in adjacent(*tile) {
+            if let Some(&Some(region)) = region_map.get(neighbor) {
+                let edge = edges.entry(region).or_insert_with(|| vec![]);
+
+                if !edge.contains(tile) {
+                    edge.push(*tile);
+                }
+            }
+        }
+    }
+
+    let mut sides = edges.keys().map(|r| *r).collect::<Vec<_>>();
+
+    sides.sort_by(|a, b| {
+        regions[*a]
+            .height
+            .partial_cmp(&regions[*b].height)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let (lower, upper) = match (sides.first(), sides.last()) {
+        (Some(&lower), Some(&upper)) if lower != upper => {
+            (Some(lower), Some(upper))
+        },
+        (Some(&side), _) if regions[side].height > height => (None, Some(side)),
+        (Some(&side), _) => (Some(side), None),
+        _ => (None, None),
+    };
+
+    Ramp {
+        top_center: upper.map_or(center, |r| centroid(&edges[&r])),
+        bottom_center: lower.map_or(center, |r| centroid(&edges[&r])),
+        upper_region: upper,
+        lower_region: lower,
+
+        tiles: tiles,
+        center: center,
+    }
+}
+
+fn adjacent(tile: Point2I) -> Vec<Point2I> {
+    vec![
+        tile + Point2I::new(1, 0),
+        tile + Point2I::new(-1, 0),
+        tile + Point2I::new(0, 1),
+        tile + Point2I::new(0, -1),
+    ]
+}
+
+fn centroid(tiles: &[Point2I]) -> Point2 {
+    let sum = tiles
+        .iter()
+        .fold(Point2::origin(), |sum, t| sum + tile_center(*t).coords);
+
+    sum / tiles.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid<T, F>(width: u32, height: u32, f: F) -> Grid<T>
+    where
+        T: Clone + Default,
+        F: Fn(i32, i32) -> T,
+    {
+        let mut grid = Grid::new(width, height, T::default());
+
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                grid.set(Point2I::new(x, y), f(x, y));
+            }
+        }
+
+        grid
+    }
+
+    #[test]
+    fn test_choke() {
+        // two rooms joined by a corridor that is 2 tiles wide
+        let pathable = grid(30, 12, |x, y| {
+            x < 12 || x >= 18 || (y >= 5 && y < 7)
+        });
+        let placeable = pathable.clone();
+        let heights = grid(30, 12, |_, _| 0.0);
+
+        let terrain =
+            TerrainAnalysis::from_grids(&pathable, &placeable, &heights);
+
+        assert_eq!(terrain.get_regions().len(), 2);
+        assert_eq!(terrain.get_chokes().len(), 1);
+        assert!(terrain.get_ramps().is_empty());
+
+        let left = terrain.get_region_at(Point2::new(5.0, 5.0)).unwrap();
+        let right = terrain.get_region_at(Point2::new(25.0, 5.0)).unwrap();
+
+        assert!(left.get_id() != right.get_id());
+        assert!(terrain.is_connected(left.get_id(), right.get_id()));
+        assert!(!terrain.is_connected(left.get_id(), 2));
+        assert!(!terrain.is_connected(2, right.get_id()));
+
+        let choke = &terrain.get_chokes()[0];
+
+        assert!(choke.get_center().x > 11.0 && choke.get_center().x < 19.0);
+        assert!(choke.get_width() < 3.0);
+    }
+
+    #[test]
+    fn test_ramp() {
+        // high ground on the left, low ground on the right
+        let pathable = grid(20, 10, |_, _| true);
+        let placeable = grid(20, 10, |x, _| x < 8 || x >= 12);
+        let heights = grid(20, 10, |x, _| match x {
+            x if x < 8 => 10.0,
+            x if x >= 12 => 0.0,
+            x => 10.0 - 2.5 * (x - 7) as f32,
+        });
+
+        let terrain =
+            TerrainAnalysis::from_grids(&pathable, &placeable, &heights);
+
+        assert_eq!(terrain.get_regions().len(), 2);
+        assert_eq!(terrain.get_ramps().len(), 1);
+        assert!(terrain.get_chokes().is_empty());
+
+        let ramp = &terrain.get_ramps()[0];
+        let upper = ramp.get_upper_region().unwrap();
+        let lower = ramp.get_lower_region().unwrap();
+
+        assert_eq!(terrain.get_regions()[upper].get_height(), 10.0);
+        assert_eq!(terrain.get_regions()[lower].get_height(), 0.0);
+        assert_eq!(ramp.get_top_center().x, 8.5);
+        assert_eq!(ramp.get_bottom_center().x, 11.5);
+        assert!(terrain.is_connected(upper, lower));
+    }
+}
@@ -0,0 +1,348 @@
+//! Contains a blocking, callback-based API for writing simple bots.
+//!
+//! The rest of the library is built around futures and channels, which is
+//! very flexible, but it can be a lot to take in if you just want to write a
+//! bot. This module hides all of that behind the Bot trait, which is much
+//! closer to the Agent callbacks in s2client-api.
+//!
+//! ```no_run
+//! extern crate sc2;
+//!
+//! use sc2::{
+//!     action::Action,
+//!     ai::OpponentBuilder,
+//!     bot::{run_game, Bot},
+//!     data::{GameSetup, Map, Race},
+//!     observer::Observation,
+//!
+//!     LauncherSettings,
+//!     MeleeBuilder,
+//! };
+//!
+//! struct SimpleBot;
+//!
+//! impl Bot for SimpleBot {
+//!     fn on_step(&mut self, observation: &Observation) -> Vec<Action> {
+//!         println!("step {}", observation.get_current_step());
+//!
+//!         vec![]
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let melee = MeleeBuilder::new()
+//!         .add_player(OpponentBuilder::new())
+//!         .launcher_settings(LauncherSettings::new())
+//!         .one_and_done(GameSetup::new(Map::LocalMap(
+//!             "maps/Ladder/(2)Bel'ShirVestigeLE (Void).SC2Map".into()
+//!         )))
+//!         .step_interval(1);
+//!
+//!     run_game(SimpleBot, Race::Terran, melee).unwrap();
+//! }
+//! ```
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures::unsync::mpsc;
+use tokio_core::reactor;
+
+use action::{Action, ActionClient};
+use agent::AgentBuilder;
+use data::{MapInfo, Race, Unit, UnitType, Upgrade};
+use observer::{Event, EventAck, EventKind, Observation, ObserverClient};
+use services::melee_service::MeleeBuilder;
+use {Error, Result};
+
+/// A bot that reacts to game events through callbacks.
+///
+/// Every callback is invoked synchronously on the event loop, so there is no
+/// need to deal with futures at all. Only on_step is required, the other
+/// callbacks do nothing by default.
+pub trait Bot {
+    /// Called once at the start of every game.
+    fn on_start(&mut self, _map_info: &MapInfo, _observation: &Observation) {
+    }
+    /// Called every time the game steps.
+    ///
+    /// The returned actions are sent to the game before it steps again.
+    fn on_step(&mut self, observation: &Observation) -> Vec<Action>;
+    /// Called after every step to choose how many game loops to advance
+    /// before the next one.
+    ///
+    /// This only applies when the game is stepped with an interval, None
+    /// keeps the interval of the melee.
+    fn next_step_size(&mut self) -> Option<u32> {
+        None
+    }
+    /// Called once the game has ended.
+    fn on_end(&mut self) {}
+
+    /// Called when a unit was created.
+    fn on_unit_created(&mut self, _unit: Rc<Unit>) {}
+    /// Called when a unit was destroyed.
+    fn on_unit_destroyed(&mut self, _unit: Rc<Unit>) {}
+    /// Called when a unit does not have any orders.
+    fn on_unit_idle(&mut self, _unit: Rc<Unit>) {}
+    /// Called when an enemy unit was detected.
+    fn on_unit_detected(&mut self, _unit: Rc<Unit>) {}
+    /// Called when a unit lost health or shields.
+    fn on_unit_damaged(&mut self, _unit: Rc<Unit>, _amount: f32) {}
+    /// Called when a unit changed type (previous type is given).
+    fn on_unit_type_changed(&mut self, _unit: Rc<Unit>, _previous: UnitType) {
+    }
+    /// Called when a visible unit was lost in the fog of war.
+    fn on_unit_entered_fog(&mut self, _unit: Rc<Unit>) {}
+    /// Called when a unit changed owners (previous owner is given).
+    fn on_unit_owner_changed(&mut self, _unit: Rc<Unit>, _previous: i32) {}
+    /// Called when a unit started constructing a building.
+    fn on_construction_started(&mut self, _unit: Rc<Unit>) {}
+    /// Called when a unit finished constructing a building.
+    fn on_building_completed(&mut self, _unit: Rc<Unit>) {}
+    /// Called when an upgrade completed.
+    fn on_upgrade_completed(&mut self, _upgrade: Upgrade) {}
+
+    /// Called with the number of nydus worms detected.
+    fn on_nydus_worms_detected(&mut self, _count: u32) {}
+    /// Called with the number of nukes launched.
+    fn on_nukes_detected(&mut self, _count: u32) {}
+
+    /// Called when a realtime step took longer than a game loop (the time
+    /// over budget is given).
+    fn on_step_overrun(&mut self, _overrun: Duration) {}
+}
+
+/// Play a melee with the given bot.
+///
+/// This creates its own event loop and blocks until the melee is finished.
+/// The melee should be configured with everything except the bot itself and
+/// the reactor handle (ie. launcher settings, suite, opponent, and update
+/// scheme).
+pub fn run_game<B>(bot: B, race: Race, melee: MeleeBuilder) -> Result<()>
+where
+    B: Bot + 'static,
+{
+    let mut core = reactor::Core::new()?;
+    let handle = core.handle();
+
+    let mut agent = AgentBuilder::new().race(race);
+
+    BotDriver::new(
+        bot,
+        agent.add_observer_client(),
+        agent.add_action_client(),
+    ).spawn(&handle, agent.take_event_stream().unwrap())?;
+
+    let melee = melee.add_player(agent).handle(&handle).create()?;
+
+    core.run(melee.into_future())
+}
+
+/// Translates the event stream into Bot callbacks.
+struct BotDriver<B: Bot + 'static> {
+    bot: B,
+
+    observer: ObserverClient,
+    action: ActionClient,
+}
+
+impl<B: Bot + 'static> BotDriver<B> {
+    fn new(bot: B, observer: ObserverClient, action: ActionClient) -> Self {
+        Self {
+            bot: bot,
+
+            observer: observer,
+            action: action,
+        }
+    }
+
+    fn spawn(
+        self,
+        handle: &reactor::Handle,
+        rx: mpsc::Receiver<(Event, EventAck)>,
+    ) -> Result<()> {
+        handle.spawn(self.run(rx).map_err(|e| panic!("{:#?}", e)));
+
+        Ok(())
+    }
+
+    #[async]
+    fn run(mut self, rx: mpsc::Receiver<(Event, EventAck)>) -> Result<()> {
+        #[async]
+        for (e, ack) in rx.map_err(|_| -> Error { unreachable!() }) {
+            let is_step = e.get_kind() == EventKind::Step;
+
+            self = await!(self.on_event(e))?;
+
+            let step_size = if is_step {
+                self.bot.next_step_size()
+            } else {
+                None
+            };
+
+            match step_size {
+                Some(steps) => await!(ack.done_and_step(steps))?,
+                None => await!(ack.done())?,
+            }
+        }
+
+        Ok(())
+    }
+
+    #[async]
+    fn on_event(mut self, e: Event) -> Result<Self> {
+        match e {
+            Event::GameLoaded => (),
+            Event::GameStarted => {
+                let map_info = await!(self.observer.get_map_info())?;
+                let observation = await!(self.observer.observe())?;
+
+                self.bot.on_start(&*map_info, &*observation);
+            },
+            Event::GameEnded => self.bot.on_end(),
+
+            Event::UnitDestroyed(unit) => self.bot.on_unit_destroyed(unit),
+            Event::UnitCreated(unit) => self.bot.on_unit_created(unit),
+            Event::UnitIdle(unit) => self.bot.on_unit_idle(unit),
+            Event::UnitDetected(unit) => self.bot.on_unit_detected(unit),
+            Event::UnitDamaged(unit, amount) => {
+                self.bot.on_unit_damaged(unit, amount)
+            },
+            Event::UnitTypeChanged(unit, previous) => {
+                self.bot.on_unit_type_changed(unit, previous)
+            },
+            Event::UnitEnteredFog(unit) => self.bot.on_unit_entered_fog(unit),
+            Event::UnitOwnerChanged(unit, previous) => {
+                self.bot.on_unit_owner_changed(unit, previous)
+            },
+
+            Event::UpgradeCompleted(upgrade) => {
+                self.bot.on_upgrade_completed(upgrade)
+            },
+            Event::ConstructionStarted(unit) => {
+                self.bot.on_construction_started(unit)
+            },
+            Event::BuildingCompleted(unit) => {
+                self.bot.on_building_completed(unit)
+            },
+
+            Event::NydusWormsDetected(count) => {
+                self.bot.on_nydus_worms_detected(count)
+            },
+            Event::NukesDetected(count) => self.bot.on_nukes_detected(count),
+
+            Event::Step => {
+                let observation = await!(self.observer.observe())?;
+                let actions = self.bot.on_step(&*observation);
+
+                for action in actions {
+                    await!(self.action.send_action(action))?;
+                }
+            },
+            Event::StepOverrun(overrun) => self.bot.on_step_overrun(overrun),
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    use sc2_proto::raw;
+
+    use fixtures::{into_unit, raw_unit};
+
+    /// Records the callbacks that were invoked.
+    struct RecordingBot {
+        calls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl RecordingBot {
+        fn record(&mut self, call: String) {
+            self.calls.borrow_mut().push(call);
+        }
+    }
+
+    impl Bot for RecordingBot {
+        fn on_step(&mut self, _: &Observation) -> Vec<Action> {
+            self.record("step".to_string());
+            vec![]
+        }
+        fn on_end(&mut self) {
+            self.record("end".to_string());
+        }
+        fn on_unit_created(&mut self, unit: Rc<Unit>) {
+            self.record(format!("created {}", unit.get_tag()));
+        }
+        fn on_unit_destroyed(&mut self, unit: Rc<Unit>) {
+            self.record(format!("destroyed {}", unit.get_tag()));
+        }
+        fn on_unit_damaged(&mut self, unit: Rc<Unit>, amount: f32) {
+            self.record(format!("damaged {} {}", unit.get_tag(), amount));
+        }
+        fn on_unit_type_changed(&mut self, unit: Rc<Unit>, previous: UnitType) {
+            self.record(format!("changed {} {:?}", unit.get_tag(), previous));
+        }
+        fn on_upgrade_completed(&mut self, upgrade: Upgrade) {
+            self.record(format!("upgrade {:?}", upgrade));
+        }
+        fn on_nukes_detected(&mut self, count: u32) {
+            self.record(format!("nukes {}", count));
+        }
+        fn on_step_overrun(&mut self, overrun: Duration) {
+            self.record(format!("overrun {}", overrun.subsec_nanos() / 1000));
+        }
+    }
+
+    #[test]
+    fn test_callbacks() {
+        let mut core = reactor::Core::new().unwrap();
+        let agent = AgentBuilder::new();
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let mut driver = BotDriver::new(
+            RecordingBot {
+                calls: Rc::clone(&calls),
+            },
+            agent.add_observer_client(),
+            agent.add_action_client(),
+        );
+
+        let unit = |tag| into_unit(raw_unit(tag, 48, raw::Alliance::Domestic));
+        let events = vec![
+            Event::GameLoaded,
+            Event::UnitCreated(unit(1)),
+            Event::UnitDamaged(unit(1), 10.0),
+            Event::UnitTypeChanged(unit(2), UnitType::TerranCommandCenter),
+            Event::UnitDestroyed(unit(1)),
+            Event::UpgradeCompleted(Upgrade::TerranBuildingArmor),
+            Event::NukesDetected(2),
+            Event::StepOverrun(Duration::from_millis(5)),
+            Event::GameEnded,
+        ];
+
+        for e in events {
+            driver = core.run(driver.on_event(e)).unwrap();
+        }
+
+        assert_eq!(
+            *calls.borrow(),
+            vec![
+                "created 1",
+                "damaged 1 10",
+                "changed 2 TerranCommandCenter",
+                "destroyed 1",
+                "upgrade TerranBuildingArmor",
+                "nukes 2",
+                "overrun 5000",
+                "end",
+            ]
+        );
+    }
+}
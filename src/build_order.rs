@@ -0,0 +1,860 @@
+//! Contains a declarative build order executor.
+//!
+//! A build order is a list of steps like "14 pylon, 16 gateway, 17 gas". The
+//! BuildOrder keeps track of which steps have been started or finished and
+//! turns the next step into an Action as soon as its trigger is met and the
+//! player can afford it.
+//!
+//! The BuildOrder does not talk to the game by itself. Feed it the events
+//! from the agent with on_event and call update every step, then send the
+//! returned actions with the ActionClient.
+
+use std::f32::consts::PI;
+use std::fmt;
+use std::rc::Rc;
+
+use action::{Action, ActionTarget};
+use data::{
+    Ability,
+    Alliance,
+    Point2,
+    Tag,
+    Unit,
+    UnitType,
+    Upgrade,
+    Vector2,
+};
+use observer::{Event, Observation};
+use tech_tree::TechTree;
+
+/// Something that a build order step produces.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BuildItem {
+    /// Build a structure or train a unit.
+    Unit(UnitType),
+    /// Research an upgrade.
+    Upgrade(Upgrade),
+}
+
+impl From<UnitType> for BuildItem {
+    fn from(unit_type: UnitType) -> Self {
+        BuildItem::Unit(unit_type)
+    }
+}
+
+impl From<Upgrade> for BuildItem {
+    fn from(upgrade: Upgrade) -> Self {
+        BuildItem::Upgrade(upgrade)
+    }
+}
+
+/// Condition that must be met before a step is started.
+#[derive(Clone)]
+pub enum Trigger {
+    /// Start the step once the supply used reaches this amount.
+    Supply(u32),
+    /// Start the step once the condition returns true.
+    Condition(Rc<Fn(&Observation) -> bool>),
+}
+
+impl Trigger {
+    fn is_met(&self, observation: &Observation) -> bool {
+        match *self {
+            Trigger::Supply(food) => observation.get_food_used() >= food,
+            Trigger::Condition(ref condition) => condition(observation),
+        }
+    }
+}
+
+impl fmt::Debug for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Trigger::Supply(food) => write!(f, "Supply({})", food),
+            Trigger::Condition(_) => write!(f, "Condition"),
+        }
+    }
+}
+
+/// Progress of a build order step.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StepStatus {
+    /// Waiting for the trigger, resources, or a builder.
+    Pending,
+    /// The order was given to the builder, but the item was not started yet.
+    Dispatched {
+        /// Unit that was given the order.
+        builder: Tag,
+        /// Game step when the order was given.
+        step: u32,
+    },
+    /// The structure is under construction.
+    Started(Tag),
+    /// The item is finished.
+    Done,
+    /// The step was retried too many times.
+    Failed,
+}
+
+/// A single step of a build order.
+#[derive(Debug, Clone)]
+pub struct BuildStep {
+    trigger: Trigger,
+    item: BuildItem,
+    status: StepStatus,
+    attempts: u32,
+}
+
+impl BuildStep {
+    /// Condition that starts this step.
+    pub fn get_trigger(&self) -> &Trigger {
+        &self.trigger
+    }
+    /// What this step produces.
+    pub fn get_item(&self) -> BuildItem {
+        self.item
+    }
+    /// Current progress of this step.
+    pub fn get_status(&self) -> StepStatus {
+        self.status
+    }
+    /// Number of times this step was dispatched.
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    fn is_dispatched_to(&self, tag: Tag) -> bool {
+        match self.status {
+            StepStatus::Dispatched { builder, .. } => builder == tag,
+            _ => false,
+        }
+    }
+}
+
+/// Chooses where to place a structure.
+///
+/// Called with the current observation, the structure type, and the number
+/// of placements attempted so far by the build order (so that a failed
+/// placement is not tried again).
+pub type Placement = Rc<Fn(&Observation, UnitType, u32) -> Option<Point2>>;
+
+/// Executes a list of build steps in order.
+#[derive(Clone)]
+pub struct BuildOrder {
+    tech_tree: TechTree,
+    steps: Vec<BuildStep>,
+
+    placement: Placement,
+    placements: u32,
+    max_attempts: u32,
+}
+
+impl BuildOrder {
+    /// Create an empty build order.
+    pub fn new(tech_tree: TechTree) -> Self {
+        Self {
+            tech_tree: tech_tree,
+            steps: vec![],
+
+            placement: Rc::new(place_around_townhall),
+            placements: 0,
+            max_attempts: 10,
+        }
+    }
+
+    /// Add a step that starts when the supply used reaches the given amount.
+    pub fn supply<T: Into<BuildItem>>(self, food: u32, item: T) -> Self {
+        self.trigger(Trigger::Supply(food), item)
+    }
+
+    /// Add a step that starts when the condition is met.
+    pub fn when<F, T>(self, condition: F, item: T) -> Self
+    where
+        F: Fn(&Observation) -> bool + 'static,
+        T: Into<BuildItem>,
+    {
+        self.trigger(Trigger::Condition(Rc::new(condition)), item)
+    }
+
+    /// Add a step with the given trigger.
+    pub fn trigger<T>(mut self, trigger: Trigger, item: T) -> Self
+    where
+        T: Into<BuildItem>,
+    {
+        self.steps.push(BuildStep {
+            trigger: trigger,
+            item: item.into(),
+            status: StepStatus::Pending,
+            attempts: 0,
+        });
+
+        self
+    }
+
+    /// Use a custom structure placement (default places structures in rings
+    /// around the first townhall).
+    pub fn placement<F>(self, placement: F) -> Self
+    where
+        F: Fn(&Observation, UnitType, u32) -> Option<Point2> + 'static,
+    {
+        Self {
+            placement: Rc::new(placement),
+            ..self
+        }
+    }
+
+    /// Number of times a step is dispatched before it fails (default is 10).
+    pub fn max_attempts(self, attempts: u32) -> Self {
+        Self {
+            max_attempts: attempts,
+            ..self
+        }
+    }
+
+    /// All steps of the build order.
+    pub fn get_steps(&self) -> &[BuildStep] {
+        &self.steps
+    }
+
+    /// Whether every step is either done or failed.
+    pub fn is_finished(&self) -> bool {
+        self.steps.iter().all(|s| match s.status {
+            StepStatus::Done | StepStatus::Failed => true,
+            _ => false,
+        })
+    }
+
+    /// Update the status of the steps with an event from the game.
+    pub fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::ConstructionStarted(ref unit) => {
+                let item = BuildItem::Unit(unit.get_unit_type());
+
+                if let Some(step) = self.find_dispatched(item) {
+                    step.status = StepStatus::Started(unit.get_tag());
+                }
+            },
+            Event::UnitCreated(ref unit) => {
+                // structures are handled by ConstructionStarted
+                if unit.get_build_progress() >= 1.0 {
+                    let item = BuildItem::Unit(unit.get_unit_type());
+
+                    if let Some(step) = self.find_dispatched(item) {
+                        step.status = StepStatus::Done;
+                    }
+                }
+            },
+            Event::BuildingCompleted(ref unit) => {
+                for step in &mut self.steps {
+                    if step.status == StepStatus::Started(unit.get_tag()) {
+                        step.status = StepStatus::Done;
+                    }
+                }
+            },
+            Event::UnitTypeChanged(ref unit, _) => {
+                // morphs keep the tag of the unit that was given the order
+                let item = BuildItem::Unit(unit.get_unit_type());
+
+                for step in &mut self.steps {
+                    if step.item == item
+                        && step.is_dispatched_to(unit.get_tag())
+                    {
+                        step.status = StepStatus::Done;
+                        break;
+                    }
+                }
+            },
+            Event::UnitDestroyed(ref unit) => {
+                // the structure was destroyed before it was finished
+                for step in &mut self.steps {
+                    if step.status == StepStatus::Started(unit.get_tag())
+                        || step.is_dispatched_to(unit.get_tag())
+                    {
+                        step.status = StepStatus::Pending;
+                    }
+                }
+            },
+            Event::UpgradeCompleted(upgrade) => {
+                let item = BuildItem::Upgrade(upgrade);
+
+                for step in &mut self.steps {
+                    if step.item == item && step.status != StepStatus::Done {
+                        step.status = StepStatus::Done;
+                        break;
+                    }
+                }
+            },
+            _ => (),
+        }
+    }
+
+    /// Check the steps against the current observation.
+    ///
+    /// Steps whose builder dropped the order are retried, and the next
+    /// pending step is dispatched if its trigger is met and it can be
+    /// afforded. Steps are dispatched strictly in order, and at most one step
+    /// is dispatched per update. Structures are only paid for once the worker
+    /// reaches the site, so their cost is held back while they are
+    /// dispatched, and the following steps do not spend the same resources.
+    pub fn update(&mut self, observation: &Observation) -> Vec<Action> {
+        self.retry_dropped(observation);
+
+        let next = self.steps
+            .iter()
+            .position(|s| s.status == StepStatus::Pending);
+
+        let index = match next {
+            Some(index) => index,
+            None => return vec![],
+        };
+
+        if !self.steps[index].trigger.is_met(observation) {
+            return vec![];
+        }
+
+        let item = self.steps[index].item;
+
+        match self.dispatch(item, observation) {
+            Some((builder, action)) => {
+                let step = &mut self.steps[index];
+
+                step.attempts += 1;
+                step.status = StepStatus::Dispatched {
+                    builder: builder,
+                    step: observation.get_current_step(),
+                };
+
+                vec![action]
+            },
+            None => vec![],
+        }
+    }
+
+    /// Put dispatched steps back into the queue if the builder is gone or is
+    /// no longer carrying out the order (ie. the placement was invalid).
+    fn retry_dropped(&mut self, observation: &Observation) {
+        for i in 0..self.steps.len() {
+            let (builder, step) = match self.steps[i].status {
+                StepStatus::Dispatched { builder, step } => (builder, step),
+                _ => continue,
+            };
+
+            if step >= observation.get_current_step() {
+                // the order has not reached the game yet
+                continue;
+            }
+
+            let ability = match self.get_ability(self.steps[i].item) {
+                Some(ability) => ability,
+                None => continue,
+            };
+
+            let is_working = observation
+                .get_units()
+                .iter()
+                .find(|u| u.get_tag() == builder)
+                .map_or(false, |u| {
+                    u.get_orders().iter().any(|o| o.get_ability() == ability)
+                });
+
+            if !is_working {
+                let step = &mut self.steps[i];
+
+                step.status = if step.attempts >= self.max_attempts {
+                    StepStatus::Failed
+                } else {
+                    StepStatus::Pending
+                };
+            }
+        }
+    }
+
+    /// Choose a builder and a target for the item.
+    fn dispatch(
+        &mut self,
+        item: BuildItem,
+        observation: &Observation,
+    ) -> Option<(Tag, Action)> {
+        let ability = self.get_ability(item)?;
+        let (minerals, vespene) = self.get_cost(item)?;
+        let (reserved_minerals, reserved_vespene) = self.get_reserved();
+
+        if observation.get_minerals() < minerals + reserved_minerals
+            || observation.get_vespene() < vespene + reserved_vespene
+        {
+            return None;
+        }
+
+        match item {
+            BuildItem::Upgrade(upgrade) => {
+                if !self.tech_tree.can_research(upgrade, observation) {
+                    return None;
+                }
+
+                let researchers = self.tech_tree.get_researchers(upgrade);
+                let researcher = self.tech_tree
+                    .get_idle_producers(researchers, None, observation)
+                    .into_iter()
+                    .next()?;
+
+                Some((
+                    researcher.get_tag(),
+                    Action::new(ability).unit_tags(vec![researcher.get_tag()]),
+                ))
+            },
+            BuildItem::Unit(unit_type) => {
+                if !self.tech_tree.can_build(unit_type, observation) {
+                    return None;
+                }
+
+                let producers = self.tech_tree.get_producers(unit_type);
+                let add_on = if self.tech_tree.requires_add_on(unit_type) {
+                    self.tech_tree.get_requirement(unit_type)
+                } else {
+                    None
+                };
+                let candidates = self.tech_tree.get_idle_producers(
+                    producers,
+                    add_on,
+                    observation,
+                );
+
+                if self.is_built_by_worker(item) {
+                    self.dispatch_structure(
+                        unit_type,
+                        ability,
+                        candidates,
+                        observation,
+                    )
+                } else {
+                    let producer = candidates.into_iter().next()?;
+                    let tag = producer.get_tag();
+                    let action = Action::new(ability).unit_tags(vec![tag]);
+
+                    if is_add_on(unit_type) {
+                        Some((
+                            producer.get_tag(),
+                            action.target(ActionTarget::Location(
+                                producer.get_pos_2d(),
+                            )),
+                        ))
+                    } else {
+                        Some((producer.get_tag(), action))
+                    }
+                }
+            },
+        }
+    }
+
+    /// Send the closest available worker to build a structure.
+    fn dispatch_structure(
+        &mut self,
+        unit_type: UnitType,
+        ability: Ability,
+        workers: Vec<Rc<Unit>>,
+        observation: &Observation,
+    ) -> Option<(Tag, Action)> {
+        let target = if unit_type.is_refinery() {
+            let geyser = find_free_geyser(observation)?;
+
+            (geyser.get_pos_2d(), ActionTarget::Unit(geyser.get_tag()))
+        } else {
+            let placement = Rc::clone(&self.placement);
+            let pos = placement(observation, unit_type, self.placements)?;
+
+            (pos, ActionTarget::Location(pos))
+        };
+
+        let steps = &self.steps;
+        let worker = workers
+            .into_iter()
+            .filter(|w| !steps.iter().any(|s| s.is_dispatched_to(w.get_tag())))
+            .min_by(|a, b| {
+                let da = (a.get_pos_2d() - target.0).norm_squared();
+                let db = (b.get_pos_2d() - target.0).norm_squared();
+
+                da.partial_cmp(&db).unwrap()
+            })?;
+
+        // the placement is only used up once a worker is sent there
+        if !unit_type.is_refinery() {
+            self.placements += 1;
+        }
+
+        Some((
+            worker.get_tag(),
+            Action::new(ability)
+                .unit_tags(vec![worker.get_tag()])
+                .target(target.1),
+        ))
+    }
+
+    fn get_ability(&self, item: BuildItem) -> Option<Ability> {
+        match item {
+            BuildItem::Unit(unit_type) => self.tech_tree
+                .get_unit_data(unit_type)
+                .map(|data| data.get_ability()),
+            BuildItem::Upgrade(upgrade) => self.tech_tree
+                .get_upgrade_data(upgrade)
+                .map(|data| data.get_ability()),
+        }
+    }
+
+    /// Minerals and vespene spent on the item.
+    fn get_cost(&self, item: BuildItem) -> Option<(u32, u32)> {
+        match item {
+            BuildItem::Unit(unit_type) => self.tech_tree
+                .get_unit_data(unit_type)
+                .map(|data| (data.get_mineral_cost(), data.get_vespene_cost())),
+            BuildItem::Upgrade(upgrade) => self.tech_tree
+                .get_upgrade_data(upgrade)
+                .map(|data| (data.get_mineral_cost(), data.get_vespene_cost())),
+        }
+    }
+
+    /// Resources held back for the structures whose workers are still on
+    /// their way to the site.
+    ///
+    /// Units and upgrades are paid for as soon as the order is given, so
+    /// they are not reserved.
+    fn get_reserved(&self) -> (u32, u32) {
+        let mut reserved = (0, 0);
+
+        for step in &self.steps {
+            match step.status {
+                StepStatus::Dispatched { .. }
+                    if self.is_built_by_worker(step.item) =>
+                {
+                    let (minerals, vespene) =
+                        self.get_cost(step.item).unwrap_or((0, 0));
+
+                    reserved.0 += minerals;
+                    reserved.1 += vespene;
+                },
+                _ => (),
+            }
+        }
+
+        reserved
+    }
+
+    /// Whether the item is a structure that a worker has to go and build.
+    fn is_built_by_worker(&self, item: BuildItem) -> bool {
+        match item {
+            BuildItem::Unit(unit_type) => self.tech_tree
+                .get_producers(unit_type)
+                .iter()
+                .any(|p| p.is_worker()),
+            BuildItem::Upgrade(_) => false,
+        }
+    }
+
+    fn find_dispatched(&mut self, item: BuildItem) -> Option<&mut BuildStep> {
+        self.steps.iter_mut().find(|s| {
+            s.item == item && match s.status {
+                StepStatus::Dispatched { .. } => true,
+                _ => false,
+            }
+        })
+    }
+}
+
+impl fmt::Debug for BuildOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BuildOrder")
+            .field("steps", &self.steps)
+            .field("placements", &self.placements)
+            .field("max_attempts", &self.max_attempts)
+            .finish()
+    }
+}
+
+/// Default placement that spirals outwards from the first townhall.
+fn place_around_townhall(
+    observation: &Observation,
+    _: UnitType,
+    attempt: u32,
+) -> Option<Point2> {
+    let townhall = observation
+        .get_units()
+        .iter()
+        .find(|u| {
+            u.get_alliance() == Alliance::Domestic
+                && u.get_unit_type().is_townhall()
+        })?;
+
+    let ring = (attempt / 8) as f32;
+    let angle = (attempt % 8) as f32 * PI / 4.0 + ring * PI / 8.0;
+    let distance = 7.0 + 3.0 * ring;
+
+    Some(
+        townhall.get_pos_2d()
+            + Vector2::new(angle.cos(), angle.sin()) * distance,
+    )
+}
+
+/// Closest vespene geyser to one of our townhalls that is not taken yet.
+fn find_free_geyser(observation: &Observation) -> Option<Rc<Unit>> {
+    let townhalls = observation.filter_units(|u| {
+        u.get_alliance() == Alliance::Domestic
+            && u.get_unit_type().is_townhall()
+    });
+    let refineries =
+        observation.filter_units(|u| u.get_unit_type().is_refinery());
+
+    observation
+        .filter_units(|u| {
+            u.get_alliance() == Alliance::Neutral
+                && u.get_vespene_contents() > 0
+                && !refineries.iter().any(|r| r.get_pos_2d() == u.get_pos_2d())
+        })
+        .into_iter()
+        .filter_map(|g| {
+            townhalls
+                .iter()
+                .map(|t| (t.get_pos_2d() - g.get_pos_2d()).norm_squared())
+                .min_by(|a, b| a.partial_cmp(b).unwrap())
+                .map(|d| (d, g))
+        })
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        .map(|(_, g)| g)
+}
+
+fn is_add_on(unit_type: UnitType) -> bool {
+    match unit_type {
+        UnitType::TerranBarracksTechLab
+        | UnitType::TerranBarracksReactor
+        | UnitType::TerranFactoryTechLab
+        | UnitType::TerranFactoryReactor
+        | UnitType::TerranStarportTechLab
+        | UnitType::TerranStarportReactor => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use sc2_proto::raw;
+
+    use fixtures::{self, into_unit, raw_unit, response, unit_data};
+
+    fn structure(tag: Tag, unit_type: u32, progress: f32) -> Rc<Unit> {
+        let mut unit = raw_unit(tag, unit_type, raw::Alliance::Domestic);
+
+        unit.set_build_progress(progress);
+
+        into_unit(unit)
+    }
+
+    fn build_order() -> BuildOrder {
+        BuildOrder::new(TechTree::new(
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+        )).supply(14, UnitType::TerranSupplyDepot)
+            .supply(16, UnitType::TerranBarracks)
+    }
+
+    fn depots() -> BuildOrder {
+        let tech_tree = TechTree::new(
+            Rc::from(
+                vec![
+                    unit_data(18, 318, 0, 400), // CommandCenter
+                    unit_data(45, 524, 0, 50),  // SCV
+                    unit_data(19, 319, 0, 100), // SupplyDepot
+                ].into_iter()
+                    .collect::<HashMap<_, _>>(),
+            ),
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+        );
+
+        BuildOrder::new(tech_tree)
+            .supply(14, UnitType::TerranSupplyDepot)
+            .supply(14, UnitType::TerranSupplyDepot)
+    }
+
+    /// A command center with one worker on each side of it (the worker with
+    /// the given tag is busy building a supply depot).
+    fn observation(
+        game_loop: u32,
+        minerals: u32,
+        building: &[Tag],
+    ) -> Rc<Observation> {
+        let mut units = vec![];
+        let layout = [(1, 18, 50.0), (10, 45, 45.0), (11, 45, 60.0)];
+
+        for &(tag, unit_type, x) in &layout {
+            let mut unit = raw_unit(tag, unit_type, raw::Alliance::Domestic);
+
+            unit.mut_pos().set_x(x);
+            unit.mut_pos().set_y(50.0);
+
+            if building.contains(&tag) {
+                let mut order = raw::UnitOrder::new();
+                order.set_ability_id(319);
+
+                unit.mut_orders().push(order);
+            }
+
+            units.push(unit);
+        }
+
+        let mut rsp = response(game_loop, units, vec![]);
+
+        {
+            let common = rsp.mut_observation()
+                .mut_observation()
+                .mut_player_common();
+
+            common.set_minerals(minerals);
+            common.set_food_used(14);
+            common.set_food_cap(15);
+        }
+
+        fixtures::observation(rsp)
+    }
+
+    #[test]
+    fn test_dispatch_closest_worker() {
+        let mut build = depots();
+
+        // the first placement is east of the command center
+        assert_eq!(build.update(&observation(1, 100, &[])).len(), 1);
+        assert_eq!(
+            build.steps[0].status,
+            StepStatus::Dispatched {
+                builder: 11,
+                step: 1,
+            }
+        );
+        assert_eq!(build.steps[0].attempts, 1);
+        assert_eq!(build.steps[1].status, StepStatus::Pending);
+    }
+
+    #[test]
+    fn test_reserve_dispatched() {
+        let mut build = depots();
+
+        assert_eq!(build.update(&observation(1, 150, &[])).len(), 1);
+
+        // the first worker is still walking, so its depot is not paid yet
+        assert!(build.update(&observation(2, 150, &[11])).is_empty());
+        assert_eq!(build.steps[1].status, StepStatus::Pending);
+
+        assert_eq!(build.update(&observation(3, 200, &[11])).len(), 1);
+        assert_eq!(
+            build.steps[1].status,
+            StepStatus::Dispatched {
+                builder: 10,
+                step: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_retry_dropped() {
+        let mut build = depots().max_attempts(2);
+
+        build.update(&observation(1, 100, &[]));
+
+        // the order has not reached the game yet
+        build.retry_dropped(&observation(1, 100, &[]));
+        assert_eq!(build.steps[0].get_attempts(), 1);
+        assert!(build.steps[0].is_dispatched_to(11));
+
+        // the worker dropped the order, so it is dispatched again
+        assert_eq!(build.update(&observation(2, 100, &[])).len(), 1);
+        assert_eq!(build.steps[0].get_attempts(), 2);
+        assert!(build.steps[0].is_dispatched_to(11));
+
+        build.retry_dropped(&observation(3, 100, &[]));
+        assert_eq!(build.steps[0].status, StepStatus::Failed);
+    }
+
+    #[test]
+    fn test_structure_progress() {
+        let mut build = build_order();
+
+        build.steps[1].status = StepStatus::Dispatched {
+            builder: 1,
+            step: 0,
+        };
+
+        build.on_event(&Event::ConstructionStarted(structure(2, 21, 0.1)));
+        assert_eq!(build.steps[0].status, StepStatus::Pending);
+        assert_eq!(build.steps[1].status, StepStatus::Started(2));
+
+        build.on_event(&Event::BuildingCompleted(structure(2, 21, 1.0)));
+        assert_eq!(build.steps[1].status, StepStatus::Done);
+        assert!(!build.is_finished());
+    }
+
+    #[test]
+    fn test_morph() {
+        let mut build = BuildOrder::new(TechTree::new(
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+        )).supply(16, UnitType::TerranOrbitalCommand);
+
+        build.steps[0].status = StepStatus::Dispatched {
+            builder: 1,
+            step: 0,
+        };
+
+        // another command center that became an orbital
+        build.on_event(&Event::UnitTypeChanged(
+            structure(2, 132, 1.0),
+            UnitType::TerranCommandCenter,
+        ));
+        assert!(build.steps[0].is_dispatched_to(1));
+
+        build.on_event(&Event::UnitTypeChanged(
+            structure(1, 132, 1.0),
+            UnitType::TerranCommandCenter,
+        ));
+        assert_eq!(build.steps[0].status, StepStatus::Done);
+        assert!(build.is_finished());
+
+        // the builder no longer has the morph order, which is fine now
+        build.retry_dropped(&observation(5, 0, &[]));
+        assert_eq!(build.steps[0].status, StepStatus::Done);
+    }
+
+    #[test]
+    fn test_placement_needs_worker() {
+        let mut build = depots();
+
+        // both workers are taken by other steps
+        build.steps[0].status = StepStatus::Dispatched {
+            builder: 10,
+            step: 1,
+        };
+        build.steps.push(BuildStep {
+            trigger: Trigger::Supply(0),
+            item: BuildItem::Unit(UnitType::TerranSupplyDepot),
+            status: StepStatus::Dispatched {
+                builder: 11,
+                step: 1,
+            },
+            attempts: 1,
+        });
+
+        assert!(build.update(&observation(1, 1000, &[10, 11])).is_empty());
+        assert_eq!(build.placements, 0);
+    }
+
+    #[test]
+    fn test_structure_destroyed() {
+        let mut build = build_order();
+
+        build.steps[0].status = StepStatus::Started(2);
+
+        build.on_event(&Event::UnitDestroyed(structure(2, 19, 0.5)));
+        assert_eq!(build.steps[0].status, StepStatus::Pending);
+    }
+}
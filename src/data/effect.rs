@@ -0,0 +1,113 @@
+use sc2_proto::{data, raw};
+
+use data::Point2;
+use {FromProto, IntoProto, Result};
+
+include!(concat!(env!("OUT_DIR"), "/effect.rs"));
+
+/// Effect data.
+#[derive(Debug, Clone)]
+pub struct EffectData {
+    effect: EffectId,
+    name: String,
+    friendly_name: String,
+    radius: f32,
+}
+
+impl EffectData {
+    /// Stable effect ID.
+    pub fn get_id(&self) -> EffectId {
+        self.effect
+    }
+
+    /// Effect name (corresponds to the game's catalog).
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// A more recognizable name of the effect.
+    pub fn get_friendly_name(&self) -> &str {
+        &self.friendly_name
+    }
+
+    /// Size of the circle the effect impacts.
+    pub fn get_radius(&self) -> f32 {
+        self.radius
+    }
+}
+
+impl FromProto<data::EffectData> for EffectData {
+    fn from_proto(mut data: data::EffectData) -> Result<Self> {
+        Ok(EffectData {
+            effect: EffectId::from_proto(data.get_effect_id())?,
+            name: data.take_name(),
+            friendly_name: data.take_friendly_name(),
+            radius: data.get_radius(),
+        })
+    }
+}
+
+/// Visuals of a persistent ability on the map (eg. PsiStorm).
+#[derive(Debug, Clone)]
+pub struct Effect {
+    effect: EffectId,
+    positions: Vec<Point2>,
+}
+
+impl Effect {
+    /// Stable effect ID.
+    pub fn get_id(&self) -> EffectId {
+        self.effect
+    }
+
+    /// All the positions that this effect is impacting on the map.
+    ///
+    /// Use the radius from EffectData to determine the area around each
+    /// position.
+    pub fn get_positions(&self) -> &[Point2] {
+        &self.positions
+    }
+}
+
+impl FromProto<raw::Effect> for Effect {
+    fn from_proto(effect: raw::Effect) -> Result<Self> {
+        Ok(Effect {
+            effect: EffectId::from_proto(effect.get_effect_id())?,
+            positions: effect
+                .get_pos()
+                .iter()
+                .map(|p| Point2::new(p.get_x(), p.get_y()))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invertibility() {
+        let test_element = |element: EffectId| {
+            assert_eq!(
+                element,
+                EffectId::from_proto(element.into_proto().unwrap()).unwrap()
+            )
+        };
+
+        test_element(EffectId::PsiStormPersistent);
+        test_element(EffectId::GuardianShieldPersistent);
+        test_element(EffectId::TemporalFieldGrowingBubbleCreatePersistent);
+        test_element(EffectId::TemporalFieldAfterBubbleCreatePersistent);
+        test_element(EffectId::ThermalLancesForward);
+        test_element(EffectId::ScannerSweep);
+        test_element(EffectId::NukePersistent);
+        test_element(EffectId::LiberatorTargetMorphDelayPersistent);
+        test_element(EffectId::LiberatorTargetMorphPersistent);
+        test_element(EffectId::BlindingCloudCp);
+        test_element(EffectId::RavagerCorrosiveBileCp);
+        test_element(EffectId::LurkerMp);
+
+        test_element(EffectId::Unknown(100000));
+    }
+}
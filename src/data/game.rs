@@ -2,11 +2,11 @@ use std::path::PathBuf;
 
 use sc2_proto::sc2api;
 
-use {FromProto, Result};
+use {FromProto, IntoSc2, Result};
 
 /// Result of the game.
 #[allow(missing_docs)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum GameResult {
     Win,
     Loss,
@@ -44,6 +44,15 @@ impl FromProto<sc2api::Result> for GameResult {
     }
 }
 
+impl FromProto<sc2api::PlayerResult> for PlayerResult {
+    fn from_proto(r: sc2api::PlayerResult) -> Result<PlayerResult> {
+        Ok(Self {
+            player_id: r.get_player_id(),
+            result: r.get_result().into_sc2()?,
+        })
+    }
+}
+
 /// Different ways of specifying a map.
 #[derive(Debug, Clone)]
 pub enum Map {
@@ -56,17 +65,114 @@ pub enum Map {
 /// Settings for a game.
 #[derive(Debug, Clone)]
 pub struct GameSetup {
-    map: Map,
+    maps: Vec<Map>,
+    random_seed: Option<u32>,
+    disable_fog: bool,
+    player_names: Vec<String>,
 }
 
 impl GameSetup {
     /// Create a game setup for the given map.
     pub fn new(map: Map) -> Self {
-        Self { map: map }
+        Self {
+            maps: vec![map],
+            random_seed: None,
+            disable_fog: false,
+            player_names: vec![],
+        }
+    }
+
+    /// Add a map to the rotation.
+    ///
+    /// Coordinators that play several games with the same setup (ie.
+    /// MeleeBuilder::repeat_forever) move on to the next map after each game.
+    /// A Tournament has its own map pool instead, and only plays the current
+    /// map of each setup in it.
+    pub fn add_map(mut self, map: Map) -> Self {
+        self.maps.push(map);
+        self
+    }
+
+    /// Use a fixed random seed so that games can be reproduced.
+    pub fn random_seed(self, seed: u32) -> Self {
+        Self {
+            random_seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// Reveal the whole map to every player (useful for debugging).
+    pub fn disable_fog(self, flag: bool) -> Self {
+        Self {
+            disable_fog: flag,
+            ..self
+        }
+    }
+
+    /// Names of the players, in the order they were added to the game (an
+    /// empty name keeps the default one).
+    pub fn player_names(self, names: Vec<String>) -> Self {
+        Self {
+            player_names: names,
+            ..self
+        }
     }
 
-    /// Get the map.
+    /// Get the map of the current game.
     pub fn get_map(&self) -> &Map {
-        &self.map
+        &self.maps[0]
+    }
+
+    /// Get every map of the rotation, starting with the current one.
+    pub fn get_maps(&self) -> &[Map] {
+        &self.maps
+    }
+
+    /// Get the random seed (if one was set).
+    pub fn get_random_seed(&self) -> Option<u32> {
+        self.random_seed
+    }
+
+    /// Whether the fog of war is disabled.
+    pub fn is_fog_disabled(&self) -> bool {
+        self.disable_fog
+    }
+
+    /// Get the name of a player (if one was set).
+    pub fn get_player_name(&self, index: usize) -> Option<&str> {
+        self.player_names.get(index).map(|name| &name[..])
+    }
+
+    /// The same setup with the next map of the rotation.
+    ///
+    /// This is how a Melee that repeats forever moves through the rotation.
+    pub fn next_map(&self) -> Self {
+        let mut game = self.clone();
+        let current = game.maps.remove(0);
+
+        game.maps.push(current);
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_map() {
+        let game = GameSetup::new(Map::BlizzardMap("A".to_string()))
+            .add_map(Map::BlizzardMap("B".to_string()))
+            .random_seed(7);
+
+        let next = game.next_map();
+
+        match *next.get_map() {
+            Map::BlizzardMap(ref name) => assert_eq!(name, "B"),
+            _ => panic!("expected a blizzard map"),
+        }
+
+        assert_eq!(next.get_maps().len(), 2);
+        assert_eq!(next.get_random_seed(), Some(7));
     }
 }
@@ -25,6 +25,41 @@ impl ImageData {
     pub fn get_dimensions(&self) -> (u32, u32) {
         self.dimensions
     }
+
+    /// Value of the pixel at the given image coordinates.
+    ///
+    /// Pixels are stored row by row, starting at the top-left corner of the
+    /// image. Returns None if the coordinates are outside of the image or the
+    /// image format is not supported.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<u32> {
+        let (w, h) = self.dimensions;
+
+        if x >= w || y >= h {
+            return None;
+        }
+
+        let index = (y * w + x) as usize;
+
+        match self.bits_per_pixel {
+            1 => self.data
+                .get(index / 8)
+                .map(|byte| ((byte >> (7 - index % 8)) & 1) as u32),
+            8 => self.data.get(index).map(|byte| *byte as u32),
+            32 => {
+                let start = index * 4;
+
+                if start + 4 > self.data.len() {
+                    None
+                } else {
+                    Some(self.data[start..start + 4].iter().rev().fold(
+                        0,
+                        |value, byte| (value << 8) | *byte as u32,
+                    ))
+                }
+            },
+            _ => None,
+        }
+    }
 }
 
 impl FromProto<common::ImageData> for ImageData {
@@ -39,3 +74,33 @@ impl FromProto<common::ImageData> for ImageData {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_pixel() {
+        let bits = ImageData {
+            bits_per_pixel: 1,
+            data: vec![0b1000_0001, 0b1000_0000],
+            dimensions: (3, 3),
+        };
+
+        assert_eq!(bits.get_pixel(0, 0), Some(1));
+        assert_eq!(bits.get_pixel(1, 0), Some(0));
+        assert_eq!(bits.get_pixel(1, 2), Some(1));
+        assert_eq!(bits.get_pixel(2, 2), Some(1));
+        assert_eq!(bits.get_pixel(0, 2), Some(0));
+        assert_eq!(bits.get_pixel(3, 0), None);
+
+        let bytes = ImageData {
+            bits_per_pixel: 8,
+            data: vec![0, 1, 2, 3],
+            dimensions: (2, 2),
+        };
+
+        assert_eq!(bytes.get_pixel(1, 1), Some(3));
+        assert_eq!(bytes.get_pixel(0, 2), None);
+    }
+}
@@ -43,6 +43,51 @@ impl MapInfo {
     pub fn get_enemy_start_locations(&self) -> &[Point2] {
         &self.enemy_start_locations
     }
+
+    /// Whether ground units are able to move through the given point.
+    ///
+    /// Note that this is the pathing grid from the start of the game, so it
+    /// includes the starting structures and resources, but nothing else.
+    pub fn is_pathable(&self, point: Point2) -> bool {
+        match self.sample(&self.pathing_grid, point) {
+            // older versions use 255 for blocked tiles
+            Some(value) if self.pathing_grid.get_bpp() == 8 => value != 255,
+            Some(value) => value != 0,
+            None => false,
+        }
+    }
+    /// Whether structures can be placed on the given point.
+    pub fn is_placeable(&self, point: Point2) -> bool {
+        match self.sample(&self.placement_grid, point) {
+            Some(value) if self.placement_grid.get_bpp() == 8 => value == 255,
+            Some(value) => value != 0,
+            None => false,
+        }
+    }
+    /// Height of the terrain at the given point.
+    pub fn get_terrain_height_at(&self, point: Point2) -> Option<f32> {
+        self.sample(&self.terrain_height, point)
+            .map(|value| -100.0 + 200.0 * value as f32 / 255.0)
+    }
+
+    /// Sample a grid at a point in world space.
+    fn sample(&self, image: &ImageData, point: Point2) -> Option<u32> {
+        let (w, h) = image.get_dimensions();
+
+        if point.x < 0.0 || point.y < 0.0 {
+            return None;
+        }
+
+        let (x, y) = (point.x as u32, point.y as u32);
+
+        if x >= w || y >= h {
+            return None;
+        }
+
+        // image data has an upper-left origin, while the world has a
+        // lower-left origin.
+        image.get_pixel(x, h - 1 - y)
+    }
 }
 
 impl FromProto<sc2api::ResponseGameInfo> for MapInfo {
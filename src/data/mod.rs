@@ -2,6 +2,7 @@
 
 mod ability;
 mod buff;
+mod effect;
 mod game;
 mod image;
 mod map_info;
@@ -19,18 +20,25 @@ use {FromProto, IntoSc2, Result};
 
 pub use self::ability::{Ability, AbilityData};
 pub use self::buff::{Buff, BuffData};
+pub use self::effect::{Effect, EffectData, EffectId};
 pub use self::game::{GameResult, GameSetup, Map, PlayerResult};
 pub use self::image::ImageData;
 pub use self::map_info::MapInfo;
-pub use self::player::{Difficulty, PlayerSetup, Race};
-pub use self::score::Score;
+pub use self::player::{AIBuild, Difficulty, PlayerSetup, Race};
+pub use self::score::{Score, ScoreDetails, ScoreType};
 pub use self::unit::{
     Alliance,
+    Attribute,
+    DamageBonus,
     DisplayType,
     Tag,
     Unit,
+    UnitOrder,
+    UnitOrderTarget,
     UnitType,
     UnitTypeData,
+    Weapon,
+    WeaponTargetType,
 };
 pub use self::upgrade::{Upgrade, UpgradeData};
 
@@ -97,28 +105,6 @@ pub enum Visibility {
     FullHidden,
 }
 
-/// Effect data.
-#[derive(Debug, Clone)]
-pub struct EffectData {
-    /// Stable effect ID.
-    effect: Ability,
-    /// Effect name (corresponds to game's catalog).
-    name: String,
-    /// A more recognizable name of the effect.
-    friendly_name: String,
-    /// Size of the circle the effect impacts.
-    radius: f32,
-}
-
-/// Visuals of a persistent ability on the map (eg. PsiStorm).
-#[derive(Debug, Clone)]
-pub struct Effect {
-    /// Stable effect ID.
-    effect: Ability,
-    /// All the positions that this effect is impacting on the map.
-    positions: Vec<Point2>,
-}
-
 /// Power source information for Protoss.
 #[derive(Debug, Copy, Clone)]
 pub struct PowerSource {
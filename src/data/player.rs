@@ -74,11 +74,38 @@ impl Difficulty {
     }
 }
 
+/// Strategy of built-in StarCraft II AI.
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone)]
+pub enum AIBuild {
+    Random,
+    Rush,
+    Timing,
+    Power,
+    Macro,
+    Air,
+}
+
+impl AIBuild {
+    /// convert to protobuf data
+    pub fn to_proto(&self) -> sc2api::AIBuild {
+        match *self {
+            AIBuild::Random => sc2api::AIBuild::RandomBuild,
+            AIBuild::Rush => sc2api::AIBuild::Rush,
+            AIBuild::Timing => sc2api::AIBuild::Timing,
+            AIBuild::Power => sc2api::AIBuild::Power,
+            AIBuild::Macro => sc2api::AIBuild::Macro,
+            AIBuild::Air => sc2api::AIBuild::Air,
+        }
+    }
+}
+
 /// Settings for players.
 #[derive(Debug, Copy, Clone)]
 pub enum PlayerSetup {
-    /// Add a built-in StarCraft II bot with the given race and difficulty.
-    Computer(Race, Difficulty),
+    /// Add a built-in StarCraft II bot with the given race, difficulty and
+    /// build.
+    Computer(Race, Difficulty, AIBuild),
     /// Add a user-controlled player.
     Player(Race),
     //Observer,
@@ -96,7 +123,7 @@ impl PlayerSetup {
     /// Does the PlayerSetup represent a computer?
     pub fn is_computer(&self) -> bool {
         match self {
-            &PlayerSetup::Computer(_, _) => true,
+            &PlayerSetup::Computer(_, _, _) => true,
             _ => false,
         }
     }
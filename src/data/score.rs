@@ -31,6 +31,21 @@ pub struct Score {
     details: ScoreDetails,
 }
 
+impl Score {
+    /// Method of scoring.
+    pub fn get_score_type(&self) -> ScoreType {
+        self.score_type
+    }
+    /// Overall score.
+    pub fn get_score(&self) -> f32 {
+        self.score
+    }
+    /// More detailed scoring.
+    pub fn get_details(&self) -> &ScoreDetails {
+        &self.details
+    }
+}
+
 impl FromProto<ProtoScore> for Score {
     fn from_proto(mut score: ProtoScore) -> Result<Self> {
         Ok(Self {
@@ -177,6 +192,57 @@ pub struct ScoreDetails {
     total_healed: Option<VitalScoreDetails>,
 }
 
+impl ScoreDetails {
+    /// Time elapsed while production was idle.
+    pub fn get_idle_production_time(&self) -> f32 {
+        self.idle_production_time
+    }
+    /// Time elapsed while workers were idle.
+    pub fn get_idle_worker_time(&self) -> f32 {
+        self.idle_worker_time
+    }
+    /// Total unit value.
+    pub fn get_total_value_units(&self) -> f32 {
+        self.total_value_units
+    }
+    /// Total structural value.
+    pub fn get_total_value_structures(&self) -> f32 {
+        self.total_value_structures
+    }
+    /// Value of enemy units destroyed.
+    pub fn get_killed_value_units(&self) -> f32 {
+        self.killed_value_units
+    }
+    /// Value of enemy structures destroyed.
+    pub fn get_killed_value_structures(&self) -> f32 {
+        self.killed_value_structures
+    }
+    /// Total minerals collected.
+    pub fn get_collected_minerals(&self) -> f32 {
+        self.collected_minerals
+    }
+    /// Total vespene collected.
+    pub fn get_collected_vespene(&self) -> f32 {
+        self.collected_vespene
+    }
+    /// Collection rate of minerals.
+    pub fn get_collection_rate_minerals(&self) -> f32 {
+        self.collection_rate_minerals
+    }
+    /// Collection rate of vespene.
+    pub fn get_collection_rate_vespene(&self) -> f32 {
+        self.collection_rate_vespene
+    }
+    /// Total minerals spent.
+    pub fn get_spent_minerals(&self) -> f32 {
+        self.spent_minerals
+    }
+    /// Total vespene spent.
+    pub fn get_spent_vespene(&self) -> f32 {
+        self.spent_vespene
+    }
+}
+
 impl FromProto<ProtoScoreDetails> for ScoreDetails {
     fn from_proto(mut details: ProtoScoreDetails) -> Result<Self> {
         Ok(Self {
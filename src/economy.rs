@@ -0,0 +1,700 @@
+//! Contains a worker manager that keeps every base saturated.
+//!
+//! The Economy sends idle workers to the closest town hall or refinery that
+//! still needs workers and moves workers away from the ones that have too
+//! many (ie. after a base is mined out or a new base is finished). Once every
+//! base is saturated, the remaining workers can be sent to mine minerals that
+//! do not have a town hall yet (long-distance mining).
+//!
+//! Like the BuildOrder, the Economy does not talk to the game by itself. Feed
+//! it the events from the agent with on_event, call update with each new
+//! observation, and send the returned actions with the ActionClient.
+
+use std::rc::Rc;
+
+use action::{Action, ActionTarget};
+use data::{
+    Ability,
+    Alliance,
+    DisplayType,
+    Point2,
+    Tag,
+    Unit,
+    UnitOrderTarget,
+};
+use observer::{Event, Observation};
+
+/// Minerals per game minute of each worker up to the ideal saturation (two
+/// workers per mineral field).
+const MINERALS_PER_WORKER: f32 = 55.0;
+/// Minerals per game minute of each third worker on a mineral field.
+const MINERALS_PER_EXTRA_WORKER: f32 = 20.0;
+/// Minerals per game minute of a worker mining far away from a town hall.
+const MINERALS_PER_DISTANT_WORKER: f32 = 25.0;
+/// Vespene per game minute of each worker on a refinery.
+const VESPENE_PER_WORKER: f32 = 53.0;
+
+/// Mineral fields within this distance of a town hall belong to it.
+const BASE_RADIUS: f32 = 12.0;
+/// Game loops between two rebalancing passes (about two seconds), so that
+/// workers have time to reach their new base before they are counted there.
+const BALANCE_INTERVAL: u32 = 45;
+
+/// Projected resource income in resources per game minute.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Income {
+    minerals: f32,
+    vespene: f32,
+}
+
+impl Income {
+    /// Minerals per game minute.
+    pub fn get_minerals(&self) -> f32 {
+        self.minerals
+    }
+    /// Vespene per game minute.
+    pub fn get_vespene(&self) -> f32 {
+        self.vespene
+    }
+}
+
+/// A town hall or refinery that workers can be assigned to.
+#[derive(Debug, Clone)]
+struct Site {
+    pos: Point2,
+    assigned: u32,
+    ideal: u32,
+
+    /// Mineral fields of a town hall or the refinery itself.
+    resources: Vec<Rc<Unit>>,
+}
+
+impl Site {
+    fn needs_workers(&self) -> bool {
+        self.assigned < self.ideal && !self.resources.is_empty()
+    }
+
+    /// Resource that new workers should gather from.
+    fn get_target(&self) -> Option<Tag> {
+        self.resources
+            .iter()
+            .max_by_key(|r| r.get_mineral_contents())
+            .map(|r| r.get_tag())
+    }
+}
+
+/// Assigns the workers of the current player to town halls and refineries.
+#[derive(Debug, Clone)]
+pub struct Economy {
+    gas_workers: u32,
+    long_distance: bool,
+
+    idle: Vec<Tag>,
+    distant: Vec<Tag>,
+    last_balance: Option<u32>,
+}
+
+impl Economy {
+    /// Create an economy that saturates every base and refinery.
+    pub fn new() -> Self {
+        Self {
+            gas_workers: 3,
+            long_distance: false,
+
+            idle: vec![],
+            distant: vec![],
+            last_balance: None,
+        }
+    }
+
+    /// Number of workers to assign to each refinery (defaults to 3).
+    ///
+    /// Use 0 to keep workers off gas entirely.
+    pub fn gas_workers(self, workers: u32) -> Self {
+        Self {
+            gas_workers: workers,
+            ..self
+        }
+    }
+
+    /// Send workers to minerals without a town hall once every base is
+    /// saturated (defaults to false).
+    ///
+    /// These workers are brought back as soon as a base needs them again.
+    pub fn long_distance(self, enabled: bool) -> Self {
+        Self {
+            long_distance: enabled,
+            ..self
+        }
+    }
+
+    /// Workers that are mining far away from a town hall.
+    pub fn get_distant_workers(&self) -> &[Tag] {
+        &self.distant
+    }
+
+    /// Number of workers that are needed to saturate every base and
+    /// refinery.
+    pub fn get_missing_workers(&self, observation: &Observation) -> u32 {
+        self.get_sites(observation)
+            .iter()
+            .filter(|s| !s.resources.is_empty())
+            .map(|s| s.ideal.saturating_sub(s.assigned))
+            .sum()
+    }
+
+    /// Projected income from the workers that are currently assigned.
+    pub fn get_income(&self, observation: &Observation) -> Income {
+        let distant = observation
+            .filter_units(|u| self.distant.contains(&u.get_tag()))
+            .len();
+
+        project_income(observation.get_units(), distant)
+    }
+
+    /// Keep track of the workers that become idle.
+    pub fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::UnitIdle(ref unit) => {
+                if unit.get_alliance() == Alliance::Domestic
+                    && unit.get_unit_type().is_worker()
+                    && !self.idle.contains(&unit.get_tag())
+                {
+                    self.idle.push(unit.get_tag());
+                }
+            },
+            Event::UnitDestroyed(ref unit) => {
+                let tag = unit.get_tag();
+
+                self.idle.retain(|t| *t != tag);
+                self.distant.retain(|t| *t != tag);
+            },
+            _ => (),
+        }
+    }
+
+    /// Put idle workers to work and rebalance the bases every few seconds.
+    pub fn update(&mut self, observation: &Observation) -> Vec<Action> {
+        let mut sites = self.get_sites(observation);
+        let mut actions = vec![];
+
+        for worker in self.take_idle_workers(observation) {
+            if let Some(action) = self.assign(&worker, &mut sites, observation)
+            {
+                actions.push(action);
+            }
+        }
+
+        let step = observation.get_current_step();
+
+        if self.last_balance
+            .map_or(true, |last| step >= last + BALANCE_INTERVAL)
+        {
+            self.last_balance = Some(step);
+            actions.extend(self.balance(&mut sites, observation));
+        }
+
+        actions
+    }
+
+    /// Finished town halls and refineries of the current player.
+    fn get_sites(&self, observation: &Observation) -> Vec<Site> {
+        let minerals = observation.filter_units(|u| {
+            u.get_unit_type().is_mineral_field() && has_minerals(u)
+        });
+
+        observation
+            .get_units()
+            .iter()
+            .filter(|u| {
+                u.get_alliance() == Alliance::Domestic
+                    && u.get_build_progress() >= 1.0
+            })
+            .filter_map(|u| {
+                let unit_type = u.get_unit_type();
+
+                if unit_type.is_townhall() {
+                    Some(Site {
+                        pos: u.get_pos_2d(),
+                        assigned: u.get_assigned_harvesters(),
+                        ideal: u.get_ideal_harvesters(),
+
+                        resources: minerals
+                            .iter()
+                            .filter(|m| {
+                                (m.get_pos_2d() - u.get_pos_2d()).norm()
+                                    <= BASE_RADIUS
+                            })
+                            .cloned()
+                            .collect(),
+                    })
+                } else if unit_type.is_refinery()
+                    && u.get_vespene_contents() > 0
+                {
+                    Some(Site {
+                        pos: u.get_pos_2d(),
+                        assigned: u.get_assigned_harvesters(),
+                        ideal: u.get_ideal_harvesters().min(self.gas_workers),
+
+                        resources: vec![Rc::clone(u)],
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Workers that reported being idle (or every idle worker if the game
+    /// reports some that we were not told about).
+    fn take_idle_workers(
+        &mut self,
+        observation: &Observation,
+    ) -> Vec<Rc<Unit>> {
+        let idle = self.idle.drain(..).collect::<Vec<_>>();
+        let check_all = observation.get_idle_worker_count() > 0;
+
+        observation.filter_units(|u| {
+            u.get_alliance() == Alliance::Domestic
+                && u.get_unit_type().is_worker()
+                && u.get_orders().is_empty()
+                && (check_all || idle.contains(&u.get_tag()))
+        })
+    }
+
+    /// Send a worker to the closest site that needs workers.
+    fn assign(
+        &mut self,
+        worker: &Unit,
+        sites: &mut [Site],
+        observation: &Observation,
+    ) -> Option<Action> {
+        let tag = worker.get_tag();
+        let pos = worker.get_pos_2d();
+
+        self.distant.retain(|t| *t != tag);
+
+        let target = match closest_site(sites, pos, |s| s.needs_workers()) {
+            Some(i) => {
+                sites[i].assigned += 1;
+                sites[i].get_target()?
+            },
+            None if self.long_distance => {
+                let field = find_distant_minerals(observation, pos)?;
+
+                self.distant.push(tag);
+                field.get_tag()
+            },
+            // every base is saturated, so oversaturate the closest one
+            None => {
+                let i = closest_site(sites, pos, |s| {
+                    s.resources
+                        .iter()
+                        .any(|r| r.get_unit_type().is_mineral_field())
+                })?;
+
+                sites[i].assigned += 1;
+                sites[i].get_target()?
+            },
+        };
+
+        Some(gather(tag, target))
+    }
+
+    /// Move workers from oversaturated sites (and distant minerals) to the
+    /// sites that need them.
+    fn balance(
+        &mut self,
+        sites: &mut [Site],
+        observation: &Observation,
+    ) -> Vec<Action> {
+        let workers = observation.filter_units(|u| {
+            u.get_alliance() == Alliance::Domestic
+                && u.get_unit_type().is_worker()
+        });
+
+        // distant workers come back first
+        let mut surplus = workers
+            .iter()
+            .filter(|w| self.distant.contains(&w.get_tag()))
+            .map(|w| (None, Rc::clone(w)))
+            .collect::<Vec<_>>();
+
+        for (i, site) in sites.iter().enumerate() {
+            if site.assigned <= site.ideal {
+                continue;
+            }
+
+            let extra = workers
+                .iter()
+                .filter(|w| {
+                    get_gather_target(w).map_or(false, |target| {
+                        site.resources.iter().any(|r| r.get_tag() == target)
+                    })
+                })
+                .take((site.assigned - site.ideal) as usize)
+                .map(|w| (Some(i), Rc::clone(w)));
+
+            surplus.extend(extra);
+        }
+
+        let mut actions = vec![];
+
+        for (from, worker) in surplus {
+            let pos = worker.get_pos_2d();
+            let to = match closest_site(sites, pos, |s| s.needs_workers()) {
+                Some(to) => to,
+                None => break,
+            };
+
+            if from == Some(to) {
+                continue;
+            }
+
+            if let Some(target) = sites[to].get_target() {
+                let tag = worker.get_tag();
+
+                sites[to].assigned += 1;
+                if let Some(from) = from {
+                    sites[from].assigned -= 1;
+                }
+                self.distant.retain(|t| *t != tag);
+
+                actions.push(gather(tag, target));
+            }
+        }
+
+        actions
+    }
+}
+
+/// Project the income of the finished town halls and refineries among the
+/// units.
+fn project_income(units: &[Rc<Unit>], distant_workers: usize) -> Income {
+    let mut income = Income {
+        minerals: distant_workers as f32 * MINERALS_PER_DISTANT_WORKER,
+        vespene: 0.0,
+    };
+
+    for unit in units {
+        if unit.get_alliance() != Alliance::Domestic
+            || unit.get_build_progress() < 1.0
+        {
+            continue;
+        }
+
+        let assigned = unit.get_assigned_harvesters();
+        let ideal = unit.get_ideal_harvesters();
+
+        if unit.get_unit_type().is_townhall() {
+            // up to three workers per mineral field (ideal is two)
+            let extra = assigned.saturating_sub(ideal).min(ideal / 2);
+
+            income.minerals += assigned.min(ideal) as f32
+                * MINERALS_PER_WORKER
+                + extra as f32 * MINERALS_PER_EXTRA_WORKER;
+        } else if unit.get_unit_type().is_refinery() {
+            income.vespene += assigned.min(ideal) as f32 * VESPENE_PER_WORKER;
+        }
+    }
+
+    income
+}
+
+/// Index of the closest site that passes the test.
+fn closest_site<F>(sites: &[Site], pos: Point2, test: F) -> Option<usize>
+where
+    F: Fn(&Site) -> bool,
+{
+    sites
+        .iter()
+        .enumerate()
+        .filter(|&(_, s)| test(s))
+        .min_by(|a, b| {
+            let da = (a.1.pos - pos).norm_squared();
+            let db = (b.1.pos - pos).norm_squared();
+
+            da.partial_cmp(&db).unwrap()
+        })
+        .map(|(i, _)| i)
+}
+
+/// Closest mineral field that is not next to one of our town halls.
+fn find_distant_minerals(
+    observation: &Observation,
+    pos: Point2,
+) -> Option<Rc<Unit>> {
+    let townhalls = observation.filter_units(|u| {
+        u.get_alliance() == Alliance::Domestic
+            && u.get_unit_type().is_townhall()
+    });
+
+    observation
+        .filter_units(|u| {
+            u.get_unit_type().is_mineral_field() && has_minerals(u)
+                && !townhalls.iter().any(|t| {
+                    (t.get_pos_2d() - u.get_pos_2d()).norm() <= BASE_RADIUS
+                })
+        })
+        .into_iter()
+        .min_by(|a, b| {
+            let da = (a.get_pos_2d() - pos).norm_squared();
+            let db = (b.get_pos_2d() - pos).norm_squared();
+
+            da.partial_cmp(&db).unwrap()
+        })
+}
+
+/// Whether a mineral field still has minerals left.
+///
+/// The contents are only known while the field is visible, so snapshots are
+/// assumed to have some left (mined out fields disappear from the game).
+fn has_minerals(unit: &Unit) -> bool {
+    unit.get_display_type() != DisplayType::Visible
+        || unit.get_mineral_contents() > 0
+}
+
+/// The resource that a worker is on its way to gather from.
+fn get_gather_target(worker: &Unit) -> Option<Tag> {
+    let order = worker.get_orders().first()?;
+
+    match order.get_ability() {
+        Ability::HarvestGather
+        | Ability::HarvestGatherScv
+        | Ability::HarvestGatherProbe
+        | Ability::HarvestGatherDrone => match order.get_target() {
+            Some(UnitOrderTarget::UnitTag(tag)) => Some(tag),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn gather(worker: Tag, target: Tag) -> Action {
+    Action::new(Ability::HarvestGather)
+        .unit_tags(vec![worker])
+        .target(ActionTarget::Unit(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::raw;
+
+    use fixtures::{self, into_unit, raw_unit, response};
+    use IntoProto;
+
+    fn unit(unit_type: u32, assigned: i32, ideal: i32) -> Rc<Unit> {
+        let mut unit = raw_unit(0, unit_type, raw::Alliance::Domestic);
+
+        unit.set_assigned_harvesters(assigned);
+        unit.set_ideal_harvesters(ideal);
+
+        into_unit(unit)
+    }
+
+    fn placed(
+        tag: Tag,
+        unit_type: u32,
+        alliance: raw::Alliance,
+        x: f32,
+        y: f32,
+    ) -> raw::Unit {
+        let mut unit = raw_unit(tag, unit_type, alliance);
+
+        unit.mut_pos().set_x(x);
+        unit.mut_pos().set_y(y);
+
+        unit
+    }
+
+    /// A command center (or a refinery with some gas left) at y = 50.
+    fn site(
+        tag: Tag,
+        unit_type: u32,
+        x: f32,
+        assigned: i32,
+        ideal: i32,
+    ) -> raw::Unit {
+        let mut unit = placed(tag, unit_type, raw::Alliance::Domestic, x, 50.0);
+
+        unit.set_assigned_harvesters(assigned);
+        unit.set_ideal_harvesters(ideal);
+        unit.set_vespene_contents(2000);
+
+        unit
+    }
+
+    /// A mineral field below a site.
+    fn mineral(tag: Tag, x: f32, contents: i32) -> raw::Unit {
+        let mut unit = placed(tag, 341, raw::Alliance::Neutral, x, 57.0);
+
+        unit.set_mineral_contents(contents);
+
+        unit
+    }
+
+    /// An SCV above a site, gathering from the given mineral field.
+    fn worker(tag: Tag, x: f32, gathering: Option<Tag>) -> raw::Unit {
+        let mut unit = placed(tag, 45, raw::Alliance::Domestic, x, 45.0);
+
+        if let Some(target) = gathering {
+            let mut order = raw::UnitOrder::new();
+            order.set_ability_id(295);
+            order.set_target_unit_tag(target);
+
+            unit.mut_orders().push(order);
+        }
+
+        unit
+    }
+
+    fn observation(game_loop: u32, units: Vec<raw::Unit>) -> Rc<Observation> {
+        fixtures::observation(response(game_loop, units, vec![]))
+    }
+
+    /// Idle workers reported by the game.
+    fn idle(economy: &mut Economy, workers: &[raw::Unit]) {
+        for worker in workers {
+            economy.on_event(&Event::UnitIdle(into_unit(worker.clone())));
+        }
+    }
+
+    /// The worker and the resource of each gather action (sorted by worker,
+    /// since the units of an observation are in no particular order).
+    fn gathers(actions: Vec<Action>) -> Vec<(Tag, Tag)> {
+        let mut gathers = actions
+            .into_iter()
+            .map(|action| {
+                let action = action.into_proto().unwrap();
+                let cmd = action.get_action_raw().get_unit_command();
+
+                assert_eq!(cmd.get_ability_id(), 3666); // HarvestGather
+
+                (cmd.get_unit_tags()[0], cmd.get_target_unit_tag())
+            })
+            .collect::<Vec<_>>();
+
+        gathers.sort();
+        gathers
+    }
+
+    #[test]
+    fn test_income() {
+        let income = project_income(&[unit(18, 20, 16), unit(20, 2, 3)], 1);
+
+        assert_eq!(
+            income.get_minerals(),
+            16.0 * MINERALS_PER_WORKER + 4.0 * MINERALS_PER_EXTRA_WORKER
+                + MINERALS_PER_DISTANT_WORKER
+        );
+        assert_eq!(income.get_vespene(), 2.0 * VESPENE_PER_WORKER);
+
+        let income = project_income(&[unit(18, 30, 16)], 0);
+
+        assert_eq!(
+            income.get_minerals(),
+            16.0 * MINERALS_PER_WORKER + 8.0 * MINERALS_PER_EXTRA_WORKER
+        );
+    }
+    #[test]
+    fn test_assign_idle_workers() {
+        let workers = [worker(10, 50.0, None), worker(11, 61.0, None)];
+        let observation = observation(
+            0,
+            vec![
+                site(1, 18, 50.0, 0, 4), // CommandCenter
+                site(2, 20, 61.0, 0, 3), // Refinery
+                mineral(20, 50.0, 900),
+                mineral(21, 52.0, 1500),
+                workers[0].clone(),
+                workers[1].clone(),
+            ],
+        );
+
+        // the worker next to the refinery goes there, the other one mines
+        // the richest field
+        let mut economy = Economy::new();
+        idle(&mut economy, &workers);
+
+        assert_eq!(
+            gathers(economy.update(&observation)),
+            vec![(10, 21), (11, 2)]
+        );
+        assert!(economy.update(&observation).is_empty());
+
+        // unless workers are kept off gas
+        let mut economy = Economy::new().gas_workers(0);
+        idle(&mut economy, &workers);
+
+        assert_eq!(
+            gathers(economy.update(&observation)),
+            vec![(10, 21), (11, 21)]
+        );
+    }
+
+    #[test]
+    fn test_long_distance() {
+        let workers = [worker(10, 50.0, None)];
+        let units = |assigned| {
+            vec![
+                site(1, 18, 50.0, assigned, 4),
+                mineral(20, 50.0, 900),
+                mineral(21, 52.0, 1500),
+                mineral(40, 100.0, 1800),
+                workers[0].clone(),
+            ]
+        };
+
+        // a saturated base is oversaturated by default
+        let mut economy = Economy::new();
+        idle(&mut economy, &workers);
+
+        assert_eq!(
+            gathers(economy.update(&observation(0, units(4)))),
+            vec![(10, 21)]
+        );
+
+        let mut economy = Economy::new().long_distance(true);
+        idle(&mut economy, &workers);
+
+        assert_eq!(
+            gathers(economy.update(&observation(0, units(4)))),
+            vec![(10, 40)]
+        );
+        assert_eq!(economy.get_distant_workers(), &[10]);
+
+        // and distant workers come back once the base needs them
+        assert!(economy.update(&observation(44, units(3))).is_empty());
+        assert_eq!(
+            gathers(economy.update(&observation(45, units(3)))),
+            vec![(10, 21)]
+        );
+        assert!(economy.get_distant_workers().is_empty());
+    }
+
+    #[test]
+    fn test_balance() {
+        let observation = observation(
+            0,
+            vec![
+                site(1, 18, 50.0, 6, 4),
+                site(2, 18, 100.0, 0, 4),
+                mineral(20, 50.0, 900),
+                mineral(21, 52.0, 1500),
+                mineral(22, 100.0, 1500),
+                worker(10, 50.0, Some(20)),
+                worker(11, 50.0, Some(20)),
+                worker(12, 50.0, Some(21)),
+            ],
+        );
+
+        // two of the extra workers move to the other base
+        let moved = gathers(Economy::new().update(&observation));
+
+        assert_eq!(moved.len(), 2);
+        assert!(moved[0].0 != moved[1].0);
+        assert!(moved.iter().all(|&(_, target)| target == 22));
+    }
+}
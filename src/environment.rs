@@ -0,0 +1,570 @@
+//! Contains Gym-style environments for reinforcement learning.
+//!
+//! The Environment plays a single player game against a built-in AI (usually
+//! a minigame map) and runs its own event loop, so it is driven by calling
+//! reset and step instead of consuming the event stream. Episodes are
+//! restarted in place, and every step advances the game by the step interval
+//! (frame skip) before observing it again. The VecEnvironment does the same
+//! for a batch of games that are stepped together.
+//!
+//! ```no_run
+//! extern crate sc2;
+//!
+//! use sc2::{
+//!     data::{GameSetup, Map, Race},
+//!     environment::{EnvironmentBuilder, Reward},
+//!     LauncherSettings,
+//! };
+//!
+//! fn main() {
+//!     let mut env = EnvironmentBuilder::new()
+//!         .launcher_settings(LauncherSettings::new())
+//!         .game(GameSetup::new(Map::LocalMap(
+//!             "maps/Example/MarineMicro.SC2Map".into(),
+//!         )))
+//!         .race(Race::Terran)
+//!         .step_interval(8)
+//!         .reward(Reward::Curriculum)
+//!         .create()
+//!         .unwrap();
+//!
+//!     env.reset().unwrap();
+//!
+//!     loop {
+//!         let (obs, reward, done) = env.step(vec![]).unwrap();
+//!
+//!         println!("step {} - reward {}", obs.get_current_step(), reward);
+//!
+//!         if done {
+//!             env.reset().unwrap();
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::rc::Rc;
+
+use futures::prelude::*;
+use futures::unsync::{mpsc, oneshot};
+use tokio_core::reactor;
+
+use action::{Action, ActionClient};
+use agent::AgentBuilder;
+use ai::OpponentBuilder;
+use constants::sc2_bug_tag;
+use data::{GameResult, GameSetup, Race, Score, ScoreType};
+use launcher::{LauncherSettings, PORTS_PER_INSTANCE};
+use observer::{Event, EventAck, Observation, ObserverClient};
+use services::melee_service::MeleeBuilder;
+use {Error, ErrorKind, Result};
+
+/// How the reward of each step is computed.
+pub enum Reward {
+    /// Change in the overall score since the last step.
+    Score,
+    /// Change in the curriculum score since the last step.
+    ///
+    /// Minigame maps report their own scoring in place of the melee score, so
+    /// this is the same value as Score. The difference is that reset fails
+    /// if the map does not have a curriculum score, rather than quietly
+    /// rewarding the melee score.
+    Curriculum,
+    /// Change in a value taken from the score since the last step.
+    ScoreDelta(Box<Fn(&Score) -> f32>),
+    /// 1 for a win and -1 for a loss at the end of the episode, 0 otherwise.
+    Outcome,
+}
+
+impl Reward {
+    /// Reward for going from one observation to the next.
+    fn evaluate(&self, prev: &Observation, obs: &Observation) -> f32 {
+        match *self {
+            // the score type is checked once per episode instead
+            Reward::Score | Reward::Curriculum => {
+                obs.get_score().get_score() - prev.get_score().get_score()
+            },
+            Reward::ScoreDelta(ref value) => {
+                value(obs.get_score()) - value(prev.get_score())
+            },
+            Reward::Outcome => {
+                let result = obs.get_player_results()
+                    .iter()
+                    .find(|r| r.get_player_id() == obs.get_player_id())
+                    .map(|r| r.get_result());
+
+                match result {
+                    Some(GameResult::Win) => 1.0,
+                    Some(GameResult::Loss) => -1.0,
+                    _ => 0.0,
+                }
+            },
+        }
+    }
+
+    /// Make sure that the reward can be computed for the game.
+    fn check(&self, obs: &Observation) -> Result<()> {
+        if let Reward::Curriculum = *self {
+            if obs.get_score().get_score_type() != ScoreType::Curriculum {
+                bail!(ErrorKind::InvalidMatch(
+                    "The map does not have a curriculum score".to_string()
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Build an Environment.
+pub struct EnvironmentBuilder {
+    launcher_settings: Option<LauncherSettings>,
+    game: Option<GameSetup>,
+    race: Race,
+    opponent: OpponentBuilder,
+    step_interval: u32,
+    reward: Reward,
+}
+
+impl EnvironmentBuilder {
+    /// Start building an Environment.
+    pub fn new() -> Self {
+        Self {
+            launcher_settings: None,
+            game: None,
+            race: Race::Random,
+            opponent: OpponentBuilder::new(),
+            step_interval: 1,
+            reward: Reward::Score,
+        }
+    }
+
+    /// The settings for the launcher.
+    pub fn launcher_settings(self, settings: LauncherSettings) -> Self {
+        Self {
+            launcher_settings: Some(settings),
+            ..self
+        }
+    }
+
+    /// The game to play every episode on.
+    pub fn game(self, game: GameSetup) -> Self {
+        Self {
+            game: Some(game),
+            ..self
+        }
+    }
+
+    /// Set the race of the agent (default is Random).
+    pub fn race(self, race: Race) -> Self {
+        Self {
+            race: race,
+            ..self
+        }
+    }
+
+    /// Set the built-in AI that fills the second slot of the game.
+    pub fn opponent(self, opponent: OpponentBuilder) -> Self {
+        Self {
+            opponent: opponent,
+            ..self
+        }
+    }
+
+    /// Number of game steps between each step of the agent (default is 1).
+    pub fn step_interval(self, steps: u32) -> Self {
+        Self {
+            step_interval: steps,
+            ..self
+        }
+    }
+
+    /// How the reward is computed (default is the overall score).
+    pub fn reward(self, reward: Reward) -> Self {
+        Self {
+            reward: reward,
+            ..self
+        }
+    }
+
+    /// Launch the game instance and build the Environment.
+    pub fn create(self) -> Result<Environment> {
+        Ok(Environment {
+            envs: self.create_vec(1)?,
+        })
+    }
+
+    /// Build a VecEnvironment with the given number of games.
+    ///
+    /// Every game gets its own instance and an equal share of the port range
+    /// in the LauncherSettings, so the ports never collide.
+    pub fn create_vec(self, count: u16) -> Result<VecEnvironment> {
+        if self.launcher_settings.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "EnvironmentBuilder needs LauncherSettings".to_string()
+            ))
+        } else if self.game.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "EnvironmentBuilder needs a GameSetup".to_string()
+            ))
+        }
+
+        let settings = self.launcher_settings
+            .unwrap()
+            .split_ports(count, PORTS_PER_INSTANCE)?;
+        let game = self.game.unwrap();
+
+        let core = reactor::Core::new()?;
+        let mut games = vec![];
+
+        for settings in settings {
+            games.push(Game::create(
+                &core.handle(),
+                settings,
+                game.clone(),
+                self.race,
+                self.opponent.clone(),
+                self.step_interval,
+            )?);
+        }
+
+        Ok(VecEnvironment {
+            core: core,
+            games: games,
+            reward: self.reward,
+        })
+    }
+}
+
+/// A single player game that is stepped by the caller.
+pub struct Environment {
+    envs: VecEnvironment,
+}
+
+impl Environment {
+    /// Start a new episode and get its first observation.
+    ///
+    /// An episode that is still in progress is restarted right away.
+    pub fn reset(&mut self) -> Result<Rc<Observation>> {
+        Ok(self.envs.reset()?.remove(0))
+    }
+
+    /// Send the actions of the agent and advance the game.
+    ///
+    /// Returns the new observation, the reward and whether the episode is
+    /// over (call reset to start the next one).
+    pub fn step(
+        &mut self,
+        actions: Vec<Action>,
+    ) -> Result<(Rc<Observation>, f32, bool)> {
+        if !self.envs.games[0].is_running() {
+            bail!(ErrorKind::EpisodeNotRunning)
+        }
+
+        Ok(self.envs.step(vec![actions])?.remove(0))
+    }
+}
+
+/// Several independent games that are stepped in lockstep.
+///
+/// All of the games run on the same event loop, so they advance concurrently
+/// while the batch is being stepped. Games that end are restarted by the next
+/// step, so the batch never has to wait for a reset.
+pub struct VecEnvironment {
+    core: reactor::Core,
+    games: Vec<Game>,
+    reward: Reward,
+}
+
+impl VecEnvironment {
+    /// Number of games in the batch.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Start a new episode in every game and get their first observations.
+    ///
+    /// Episodes that are still in progress are restarted right away.
+    pub fn reset(&mut self) -> Result<Vec<Rc<Observation>>> {
+        for game in &mut self.games {
+            if game.is_running() {
+                game.restart(&mut self.core)?;
+            } else {
+                game.release(&mut self.core)?;
+            }
+        }
+
+        let mut observations = vec![];
+
+        for game in &mut self.games {
+            let obs = game.wait(&mut self.core)?;
+
+            self.reward.check(&obs)?;
+            observations.push(obs);
+        }
+
+        Ok(observations)
+    }
+
+    /// Send the actions of each game and advance all of them.
+    ///
+    /// Returns the new observation, the reward and whether the episode is
+    /// over for each game. The actions for a game whose episode is over are
+    /// ignored, and its first observation of the next episode is returned
+    /// instead (with no reward).
+    pub fn step(
+        &mut self,
+        actions: Vec<Vec<Action>>,
+    ) -> Result<Vec<(Rc<Observation>, f32, bool)>> {
+        if actions.len() != self.games.len() {
+            bail!(ErrorKind::InvalidArgument(format!(
+                "expected actions for {} games, got {}",
+                self.games.len(),
+                actions.len()
+            )))
+        }
+
+        let mut previous = vec![];
+
+        for (game, actions) in self.games.iter_mut().zip(actions) {
+            if game.is_running() {
+                game.send_actions(&mut self.core, actions)?;
+                previous.push(game.observation.clone());
+            } else {
+                previous.push(None);
+            }
+
+            game.release(&mut self.core)?;
+        }
+
+        let mut results = vec![];
+
+        for (game, prev) in self.games.iter_mut().zip(previous) {
+            let obs = game.wait(&mut self.core)?;
+
+            let reward = match prev {
+                Some(prev) => self.reward.evaluate(&prev, &obs),
+                None => {
+                    self.reward.check(&obs)?;
+                    0.0
+                },
+            };
+            let done = obs.is_game_over();
+
+            results.push((obs, reward, done));
+        }
+
+        Ok(results)
+    }
+}
+
+/// One of the games of a VecEnvironment.
+struct Game {
+    events: Option<mpsc::Receiver<(Event, EventAck)>>,
+    observer: ObserverClient,
+    action: ActionClient,
+
+    melee_result: Option<oneshot::Receiver<Result<()>>>,
+    step_ack: Option<EventAck>,
+    observation: Option<Rc<Observation>>,
+}
+
+impl Game {
+    /// Spawn the coordinator of the game on the event loop.
+    fn create(
+        handle: &reactor::Handle,
+        settings: LauncherSettings,
+        game: GameSetup,
+        race: Race,
+        opponent: OpponentBuilder,
+        step_interval: u32,
+    ) -> Result<Self> {
+        let mut agent = AgentBuilder::new().race(race);
+
+        let events = agent.take_event_stream().unwrap();
+        let observer = agent.add_observer_client();
+        let action = agent.add_action_client();
+
+        let melee = MeleeBuilder::new()
+            .add_player(agent)
+            .add_player(opponent)
+            .launcher_settings(settings)
+            .restart_forever(game)
+            .step_interval(step_interval)
+            .handle(handle)
+            .create()?;
+
+        let (tx, rx) = oneshot::channel();
+
+        handle.spawn(melee.into_future().then(move |result| {
+            let _ = tx.send(result);
+            Ok(())
+        }));
+
+        Ok(Self {
+            events: Some(events),
+            observer: observer,
+            action: action,
+
+            melee_result: Some(rx),
+            step_ack: None,
+            observation: None,
+        })
+    }
+
+    /// Whether an episode has started and is not over yet.
+    fn is_running(&self) -> bool {
+        match self.observation {
+            Some(ref obs) => !obs.is_game_over(),
+            None => false,
+        }
+    }
+
+    /// Queue the actions for the next step.
+    fn send_actions(
+        &self,
+        core: &mut reactor::Core,
+        actions: Vec<Action>,
+    ) -> Result<()> {
+        for action in actions {
+            core.run(self.action.send_action(action))?;
+        }
+
+        Ok(())
+    }
+
+    /// Let the game continue past the current step.
+    fn release(&mut self, core: &mut reactor::Core) -> Result<()> {
+        if let Some(ack) = self.step_ack.take() {
+            core.run(ack.done())?;
+        }
+
+        Ok(())
+    }
+
+    /// End the current episode early and let the game restart.
+    fn restart(&mut self, core: &mut reactor::Core) -> Result<()> {
+        if let Some(ack) = self.step_ack.take() {
+            core.run(ack.done_and_restart())?;
+        }
+
+        Ok(())
+    }
+
+    /// Wait until the next step of the agent and observe it.
+    fn wait(&mut self, core: &mut reactor::Core) -> Result<Rc<Observation>> {
+        loop {
+            let events = self.events.take().unwrap();
+            let (event, events) = core.run(events.into_future())
+                .map_err(|_| -> Error {
+                    unreachable!("{}: Unable to receive event", sc2_bug_tag())
+                })?;
+
+            self.events = Some(events);
+
+            match event {
+                Some((Event::Step, ack)) => {
+                    self.step_ack = Some(ack);
+                    break;
+                },
+                Some((_, ack)) => core.run(ack.done())?,
+                None => return Err(self.get_melee_error(core)),
+            }
+        }
+
+        let obs = core.run(self.observer.observe())?;
+
+        self.observation = Some(Rc::clone(&obs));
+
+        Ok(obs)
+    }
+
+    /// Find out why the game stopped sending events.
+    fn get_melee_error(&mut self, core: &mut reactor::Core) -> Error {
+        let result = match self.melee_result.take() {
+            Some(rx) => core.run(rx),
+            None => Ok(Ok(())),
+        };
+
+        match result {
+            Ok(Err(e)) => e,
+            _ => ErrorKind::GameErrors(vec![
+                "The game stopped unexpectedly".to_string(),
+            ]).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::{sc2api, score};
+
+    use fixtures::{self, response};
+
+    /// An observation of player 1 with a curriculum score, along with the
+    /// result of the player if the game has ended.
+    fn observation(
+        score: i32,
+        result: Option<sc2api::Result>,
+    ) -> Rc<Observation> {
+        let mut rsp = response(0, vec![], vec![]);
+
+        {
+            let observation = rsp.mut_observation().mut_observation();
+
+            observation.mut_player_common().set_player_id(1);
+            observation
+                .mut_score()
+                .set_score_type(score::Score_ScoreType::Curriculum);
+            observation.mut_score().set_score(score);
+        }
+
+        if let Some(result) = result {
+            let mut player_result = sc2api::PlayerResult::new();
+            player_result.set_player_id(1);
+            player_result.set_result(result);
+
+            rsp.mut_observation().mut_player_result().push(player_result);
+            rsp.set_status(sc2api::Status::ended);
+        }
+
+        fixtures::observation(rsp)
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let prev = observation(10, None);
+        let obs = observation(25, None);
+
+        assert_eq!(Reward::Score.evaluate(&prev, &obs), 15.0);
+        assert_eq!(Reward::Curriculum.evaluate(&prev, &obs), 15.0);
+        assert_eq!(
+            Reward::ScoreDelta(Box::new(|s: &Score| s.get_score() * 2.0))
+                .evaluate(&prev, &obs),
+            30.0
+        );
+
+        let won = observation(25, Some(sc2api::Result::Victory));
+        let lost = observation(25, Some(sc2api::Result::Defeat));
+
+        assert_eq!(Reward::Outcome.evaluate(&prev, &obs), 0.0);
+        assert_eq!(Reward::Outcome.evaluate(&prev, &won), 1.0);
+        assert_eq!(Reward::Outcome.evaluate(&prev, &lost), -1.0);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut rsp = response(0, vec![], vec![]);
+        rsp.mut_observation()
+            .mut_observation()
+            .mut_score()
+            .set_score_type(score::Score_ScoreType::Melee);
+
+        let melee = fixtures::observation(rsp);
+
+        assert!(Reward::Score.check(&melee).is_ok());
+        assert!(Reward::Curriculum.check(&melee).is_err());
+        assert!(Reward::Curriculum.check(&observation(0, None)).is_ok());
+    }
+}
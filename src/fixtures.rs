@@ -0,0 +1,149 @@
+//! Fixtures shared by the unit tests.
+
+use std::rc::Rc;
+
+use sc2_proto::{common, data, raw, sc2api};
+
+use data::{MapInfo, Tag, Unit, UnitType, UnitTypeData};
+use observer::Observation;
+use services::observer_service::observe_response;
+use FromProto;
+
+/// A visible, finished unit at the origin (with the health of a marine).
+pub fn raw_unit(
+    tag: Tag,
+    unit_type: u32,
+    alliance: raw::Alliance,
+) -> raw::Unit {
+    let mut unit = raw::Unit::new();
+
+    unit.set_tag(tag);
+    unit.set_alliance(alliance);
+    unit.set_display_type(raw::DisplayType::Visible);
+    unit.set_unit_type(unit_type);
+    unit.set_build_progress(1.0);
+    unit.set_health(45.0);
+
+    unit
+}
+
+/// Game data of a unit type that is produced with the given ability.
+pub fn unit_data(
+    unit_type: u32,
+    ability: u32,
+    requirement: u32,
+    mineral_cost: u32,
+) -> (UnitType, UnitTypeData) {
+    let mut data = data::UnitTypeData::new();
+
+    data.set_unit_id(unit_type);
+    data.set_ability_id(ability);
+    data.set_tech_requirement(requirement);
+    data.set_mineral_cost(mineral_cost);
+
+    let data = UnitTypeData::from_proto(data).unwrap();
+
+    (data.get_id(), data)
+}
+
+/// Convert a raw unit into the unit exposed by the library.
+pub fn into_unit(unit: raw::Unit) -> Rc<Unit> {
+    Rc::from(Unit::from_proto(unit).unwrap())
+}
+
+/// A visible, finished unit at the given position.
+pub fn unit_at(
+    tag: Tag,
+    unit_type: u32,
+    alliance: raw::Alliance,
+    x: f32,
+    y: f32,
+) -> Rc<Unit> {
+    let mut unit = raw_unit(tag, unit_type, alliance);
+
+    unit.mut_pos().set_x(x);
+    unit.mut_pos().set_y(y);
+
+    into_unit(unit)
+}
+
+/// An observation response with the given units and the tags of the units
+/// that died since the last one.
+pub fn response(
+    game_loop: u32,
+    units: Vec<raw::Unit>,
+    dead: Vec<Tag>,
+) -> sc2api::Response {
+    let mut rsp = sc2api::Response::new();
+    rsp.set_status(sc2api::Status::in_game);
+
+    {
+        let observation = rsp.mut_observation().mut_observation();
+        observation.set_game_loop(game_loop);
+
+        for unit in units {
+            observation.mut_raw_data().mut_units().push(unit);
+        }
+
+        if !dead.is_empty() {
+            observation
+                .mut_raw_data()
+                .mut_event()
+                .set_dead_units(dead);
+        }
+    }
+
+    rsp
+}
+
+/// The observation of a response, as seen by a fresh observer.
+pub fn observation(rsp: sc2api::Response) -> Rc<Observation> {
+    observe_response(rsp)
+}
+
+/// A flat, pathable map where structures can be placed on the tiles that
+/// pass the test.
+pub fn map_info<F>(width: u32, height: u32, placeable: F) -> MapInfo
+where
+    F: Fn(u32, u32) -> bool,
+{
+    let image = |f: &Fn(u32, u32) -> u8| {
+        let mut image = common::ImageData::new();
+
+        image.set_bits_per_pixel(8);
+        image.mut_size().set_x(width as i32);
+        image.mut_size().set_y(height as i32);
+
+        // images start at the top-left corner of the map
+        for row in 0..height {
+            for x in 0..width {
+                image.mut_data().push(f(x, height - 1 - row));
+            }
+        }
+
+        image
+    };
+
+    let mut info = sc2api::ResponseGameInfo::new();
+
+    {
+        let start_raw = info.mut_start_raw();
+
+        start_raw.mut_map_size().set_x(width as i32);
+        start_raw.mut_map_size().set_y(height as i32);
+        start_raw.mut_playable_area().mut_p1().set_x(width as i32);
+        start_raw.mut_playable_area().mut_p1().set_y(height as i32);
+
+        start_raw.set_pathing_grid(image(&|_, _| 0));
+        start_raw.set_placement_grid(image(&|x, y| {
+            if placeable(x, y) {
+                255
+            } else {
+                0
+            }
+        }));
+        start_raw.set_terrain_height(image(&|_, _| 127));
+    }
+
+    MapInfo::from_proto(info).unwrap()
+}
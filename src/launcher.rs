@@ -1,5 +1,7 @@
 use std::env::home_dir;
+use std::fmt;
 use std::path::{PathBuf, MAIN_SEPARATOR};
+use std::str::FromStr;
 
 use colored::Colorize;
 use glob::glob;
@@ -8,38 +10,148 @@ use regex::Regex;
 use constants::{sc2_bug_tag, warning_tag};
 use data::Rect;
 use instance::{Instance, InstanceKind, InstanceSettings};
-use {ErrorKind, Result};
+use {Error, ErrorKind, Result};
 
 /// Endpoint port settings.
 #[allow(missing_docs)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PortSet {
     pub game_port: u16,
     pub base_port: u16,
 }
 
+impl fmt::Display for PortSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.game_port, self.base_port)
+    }
+}
+
+impl FromStr for PortSet {
+    type Err = Error;
+
+    /// Parse a port set in the form `game_port:base_port`.
+    fn from_str(s: &str) -> Result<Self> {
+        let ports = s.split(':').map(parse_port).collect::<Result<Vec<_>>>()?;
+
+        if ports.len() != 2 {
+            bail!(ErrorKind::InvalidArgument(format!(
+                "expected a port set like 5001:5002, got {:?}",
+                s
+            )))
+        }
+
+        Ok(Self {
+            game_port: ports[0],
+            base_port: ports[1],
+        })
+    }
+}
+
 /// All port settings for a game.
+///
+/// Every participant of a multiplayer game has to join with the same ports,
+/// so when the players live in separate processes these are usually agreed
+/// upon beforehand (ie. with GamePorts::from_start_port) or sent from the host
+/// to the others as a string (see the Display and FromStr impls).
 #[allow(missing_docs)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GamePorts {
     pub shared_port: u16,
     pub server_ports: PortSet,
     pub client_ports: Vec<PortSet>,
 }
 
+impl GamePorts {
+    /// Allocate consecutive ports starting at the given port.
+    ///
+    /// The shared port comes first, followed by the server ports and one
+    /// port set for each client (every player other than the host).
+    pub fn from_start_port(start_port: u16, clients: usize) -> Self {
+        Self {
+            shared_port: start_port,
+            server_ports: PortSet {
+                game_port: start_port + 1,
+                base_port: start_port + 2,
+            },
+            client_ports: (0..clients as u16)
+                .map(|i| PortSet {
+                    game_port: start_port + 3 + i * 2,
+                    base_port: start_port + 4 + i * 2,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for GamePorts {
+    /// Format the ports as `shared,server,client...` where each port set is
+    /// written as `game_port:base_port`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.shared_port, self.server_ports)?;
+
+        for client in &self.client_ports {
+            write!(f, ",{}", client)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GamePorts {
+    type Err = Error;
+
+    /// Parse ports in the form written by the Display impl.
+    fn from_str(s: &str) -> Result<Self> {
+        let parts = s.split(',').collect::<Vec<_>>();
+
+        if parts.len() < 2 {
+            bail!(ErrorKind::InvalidArgument(format!(
+                "expected game ports like 5000,5001:5002,5003:5004, got {:?}",
+                s
+            )))
+        }
+
+        Ok(Self {
+            shared_port: parse_port(parts[0])?,
+            server_ports: parts[1].parse()?,
+            client_ports: parts[2..]
+                .iter()
+                .map(|p| p.parse())
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+fn parse_port(s: &str) -> Result<u16> {
+    match s.trim().parse() {
+        Ok(port) => Ok(port),
+        Err(_) => bail!(ErrorKind::InvalidArgument(format!(
+            "expected a port, got {:?}",
+            s
+        ))),
+    }
+}
+
+/// Number of ports used by each game instance (and by the shared and server
+/// ports of a multiplayer game).
+pub const PORTS_PER_INSTANCE: u16 = 3;
+
 /// Launches game instances upon request.
 pub struct Launcher {
     exe: PathBuf,
     pwd: Option<PathBuf>,
     current_port: u16,
+    end_port: u32,
     use_wine: bool,
 }
 
 /// Builder used to create launcher.
+#[derive(Debug, Clone)]
 pub struct LauncherSettings {
     dir: Option<PathBuf>,
     use_wine: bool,
     base_port: u16,
+    port_count: Option<u16>,
 }
 
 impl LauncherSettings {
@@ -49,6 +161,7 @@ impl LauncherSettings {
             dir: None,
             use_wine: false,
             base_port: 9168,
+            port_count: None,
         }
     }
 
@@ -77,6 +190,58 @@ impl LauncherSettings {
             ..self
         }
     }
+
+    /// Limit the launcher to the given number of ports after the base port.
+    ///
+    /// Launching an instance fails once the range is used up, instead of
+    /// spilling over into ports that belong to someone else.
+    pub fn port_count(self, count: u16) -> Self {
+        Self {
+            port_count: Some(count),
+            ..self
+        }
+    }
+
+    /// Split the port range into separate settings for several launchers,
+    /// each with the given number of ports.
+    ///
+    /// Launchers created from the results never use the same ports, so they
+    /// can run side by side.
+    pub fn split_ports(
+        &self,
+        parts: u16,
+        ports_each: u16,
+    ) -> Result<Vec<Self>> {
+        let needed = parts as u32 * ports_each as u32;
+
+        if needed > self.get_port_count() {
+            bail!(ErrorKind::PortsExhausted(format!(
+                "{} ranges of {} ports do not fit in {} ports from {}",
+                parts,
+                ports_each,
+                self.get_port_count(),
+                self.base_port
+            )))
+        }
+
+        Ok((0..parts)
+            .map(|i| Self {
+                base_port: self.base_port + i * ports_each,
+                port_count: Some(ports_each),
+                ..self.clone()
+            })
+            .collect())
+    }
+
+    /// Number of ports that the launcher may use.
+    fn get_port_count(&self) -> u32 {
+        let available = 0x10000 - self.base_port as u32;
+
+        match self.port_count {
+            Some(count) => available.min(count as u32),
+            None => available,
+        }
+    }
 }
 
 impl Launcher {
@@ -109,11 +274,14 @@ impl Launcher {
             exe: exe,
             pwd: pwd,
             current_port: settings.base_port,
+            end_port: settings.base_port as u32 + settings.get_port_count(),
             use_wine: settings.use_wine,
         })
     }
 
     pub fn launch(&mut self) -> Result<Instance> {
+        let port = self.reserve_ports(PORTS_PER_INSTANCE)?;
+
         let mut instance = Instance::from_settings(InstanceSettings {
             kind: {
                 if self.use_wine {
@@ -124,7 +292,7 @@ impl Launcher {
             },
             exe: Some(self.exe.clone()),
             pwd: self.pwd.clone(),
-            address: ("127.0.0.1".into(), self.current_port),
+            address: ("127.0.0.1".into(), port),
             window_rect: Rect::<u32> {
                 x: 10,
                 y: 10,
@@ -132,32 +300,44 @@ impl Launcher {
                 h: 768,
             },
             ports: PortSet {
-                game_port: self.current_port + 1,
-                base_port: self.current_port + 2,
+                game_port: port + 1,
+                base_port: port + 2,
             },
         })?;
 
-        self.current_port += 3;
-
         instance.start()?;
 
         Ok(instance)
     }
 
     /// Create a set of ports for multiplayer games.
-    pub fn create_game_ports(&mut self) -> GamePorts {
-        let ports = GamePorts {
-            shared_port: self.current_port,
+    pub fn create_game_ports(&mut self) -> Result<GamePorts> {
+        let port = self.reserve_ports(PORTS_PER_INSTANCE)?;
+
+        Ok(GamePorts {
+            shared_port: port,
             server_ports: PortSet {
-                game_port: self.current_port + 1,
-                base_port: self.current_port + 2,
+                game_port: port + 1,
+                base_port: port + 2,
             },
             client_ports: vec![],
-        };
+        })
+    }
+
+    /// Take the next ports from the range of the launcher.
+    fn reserve_ports(&mut self, count: u16) -> Result<u16> {
+        let port = self.current_port;
+
+        if port as u32 + count as u32 > self.end_port {
+            bail!(ErrorKind::PortsExhausted(format!(
+                "launcher has no ports left after {}",
+                port
+            )))
+        }
 
-        self.current_port += 3;
+        self.current_port += count;
 
-        ports
+        Ok(port)
     }
 }
 
@@ -336,3 +516,42 @@ fn select_pwd(dir: &PathBuf, arch: ExeArch) -> Option<PathBuf> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_ports() {
+        let ports = GamePorts::from_start_port(5000, 1);
+
+        assert_eq!(ports.to_string(), "5000,5001:5002,5003:5004");
+        assert_eq!(
+            "5000,5001:5002,5003:5004".parse::<GamePorts>().unwrap(),
+            ports
+        );
+
+        assert!("5000".parse::<GamePorts>().is_err());
+        assert!("5000,5001".parse::<GamePorts>().is_err());
+    }
+
+    #[test]
+    fn test_split_ports() {
+        let settings = LauncherSettings::new().base_port(5000).port_count(10);
+        let parts = settings.split_ports(3, 3).unwrap();
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].base_port, 5000);
+        assert_eq!(parts[1].base_port, 5003);
+        assert_eq!(parts[2].base_port, 5006);
+        assert_eq!(parts[2].get_port_count(), 3);
+
+        assert!(settings.split_ports(4, 3).is_err());
+        assert!(
+            LauncherSettings::new()
+                .base_port(65530)
+                .split_ports(3, 3)
+                .is_err()
+        );
+    }
+}
@@ -168,6 +168,8 @@ extern crate tungstenite;
 extern crate url;
 
 mod constants;
+#[cfg(test)]
+mod fixtures;
 mod instance;
 mod launcher;
 mod services;
@@ -175,12 +177,22 @@ mod services;
 pub mod action;
 pub mod agent;
 pub mod ai;
+pub mod analysis;
+pub mod bot;
+pub mod build_order;
 pub mod data;
 pub mod debug;
+pub mod economy;
+pub mod environment;
 pub mod observer;
+pub mod squad;
+pub mod tech_tree;
+pub mod tournament;
 
-pub use self::launcher::LauncherSettings;
-pub use self::services::melee_service::MeleeBuilder;
+pub use self::launcher::{GamePorts, LauncherSettings, PortSet};
+pub use self::services::ladder_service::{LadderArgs, LadderBuilder};
+pub use self::services::melee_service::{MeleeBuilder, UpdateScheme};
+pub use self::services::multiplayer_service::{HostBuilder, JoinBuilder};
 
 use std::path::PathBuf;
 
@@ -235,6 +247,17 @@ error_chain! {
             description("Match settings are invalid"),
             display("Invalid Match - {}", msg)
         }
+        /// Command line arguments are missing or malformed.
+        InvalidArgument(msg: String) {
+            description("Command line arguments are missing or malformed")
+            display("Invalid argument - {}", msg)
+        }
+
+        /// The port range of a launcher is used up.
+        PortsExhausted(msg: String) {
+            description("The port range of a launcher is used up")
+            display("Ports exhausted - {}", msg)
+        }
 
         /// Client failed to open connection to the game instance.
         ClientOpenFailed(msg: String) {
@@ -263,6 +286,12 @@ error_chain! {
             display("Received errors: {:?}", errors)
         }
 
+        /// An Environment was stepped without an episode in progress.
+        EpisodeNotRunning {
+            description("No episode is in progress")
+            display("No episode is in progress, reset the Environment first")
+        }
+
         /// EventAck receiver was dropped or closed.
         ///
         /// This should not happen in sc2-rs, but any external libraries with
@@ -1,12 +1,14 @@
 //! Contains the public API of the structs that interact with the Observer.
 
+use std::cell::Cell;
 use std::rc::Rc;
+use std::time::Duration;
 
 use futures::prelude::*;
 use futures::unsync::oneshot;
 
 use constants::sc2_bug_tag;
-use data::{Unit, Upgrade};
+use data::{Unit, UnitType, Upgrade};
 use {Error, Result};
 
 pub use services::observer_service::{Observation, ObserverClient};
@@ -29,9 +31,21 @@ pub enum Event {
     UnitIdle(Rc<Unit>),
     /// A unit was detected.
     UnitDetected(Rc<Unit>),
+    /// A unit lost health or shields (amount lost since the last step).
+    UnitDamaged(Rc<Unit>, f32),
+    /// A unit changed type (morphs, sieging, burrowing, etc.).
+    ///
+    /// The unit type is the type it had before the change.
+    UnitTypeChanged(Rc<Unit>, UnitType),
+    /// A visible unit was lost in the fog of war.
+    UnitEnteredFog(Rc<Unit>),
+    /// A unit changed owners (the id is the previous owner).
+    UnitOwnerChanged(Rc<Unit>, i32),
 
     /// An upgrade completed.
     UpgradeCompleted(Upgrade),
+    /// A unit started constructing a building.
+    ConstructionStarted(Rc<Unit>),
     /// A unit finished constructing a building.
     BuildingCompleted(Rc<Unit>),
 
@@ -42,6 +56,70 @@ pub enum Event {
 
     /// Step the agent or observer.
     Step,
+    /// The last realtime step took longer than a game loop (the time over
+    /// budget is given).
+    ///
+    /// The game does not wait for the agent in realtime, so the next
+    /// observation skips the loops that were missed.
+    StepOverrun(Duration),
+}
+
+impl Event {
+    /// Get the kind of event without any of the associated data.
+    pub fn get_kind(&self) -> EventKind {
+        match *self {
+            Event::GameLoaded => EventKind::GameLoaded,
+            Event::GameStarted => EventKind::GameStarted,
+            Event::GameEnded => EventKind::GameEnded,
+
+            Event::UnitDestroyed(_) => EventKind::UnitDestroyed,
+            Event::UnitCreated(_) => EventKind::UnitCreated,
+            Event::UnitIdle(_) => EventKind::UnitIdle,
+            Event::UnitDetected(_) => EventKind::UnitDetected,
+            Event::UnitDamaged(_, _) => EventKind::UnitDamaged,
+            Event::UnitTypeChanged(_, _) => EventKind::UnitTypeChanged,
+            Event::UnitEnteredFog(_) => EventKind::UnitEnteredFog,
+            Event::UnitOwnerChanged(_, _) => EventKind::UnitOwnerChanged,
+
+            Event::UpgradeCompleted(_) => EventKind::UpgradeCompleted,
+            Event::ConstructionStarted(_) => EventKind::ConstructionStarted,
+            Event::BuildingCompleted(_) => EventKind::BuildingCompleted,
+
+            Event::NydusWormsDetected(_) => EventKind::NydusWormsDetected,
+            Event::NukesDetected(_) => EventKind::NukesDetected,
+
+            Event::Step => EventKind::Step,
+            Event::StepOverrun(_) => EventKind::StepOverrun,
+        }
+    }
+}
+
+/// The kind of an event, used to filter event subscriptions.
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EventKind {
+    GameLoaded,
+    GameStarted,
+    GameEnded,
+
+    UnitDestroyed,
+    UnitCreated,
+    UnitIdle,
+    UnitDetected,
+    UnitDamaged,
+    UnitTypeChanged,
+    UnitEnteredFog,
+    UnitOwnerChanged,
+
+    UpgradeCompleted,
+    ConstructionStarted,
+    BuildingCompleted,
+
+    NydusWormsDetected,
+    NukesDetected,
+
+    Step,
+    StepOverrun,
 }
 
 /// Notify the coordinator that we are done with this event.
@@ -52,6 +130,8 @@ pub enum Event {
 #[derive(Debug)]
 pub struct EventAck {
     tx: oneshot::Sender<()>,
+    step_request: Option<Rc<Cell<Option<u32>>>>,
+    restart_request: Option<Rc<Cell<bool>>>,
 }
 
 impl EventAck {
@@ -61,7 +141,33 @@ impl EventAck {
     /// dispatching an event to a number of subscribers and then waiting until
     /// they are done using it by joining all of the oneshot receivers.
     pub fn wrap(tx: oneshot::Sender<()>) -> Self {
-        Self { tx: tx }
+        Self {
+            tx: tx,
+            step_request: None,
+            restart_request: None,
+        }
+    }
+    /// Wrap the underlying oneshot along with a slot for step requests.
+    ///
+    /// Every subscriber of an event should share the same slot, so that the
+    /// smallest step requested by any of them wins.
+    pub fn wrap_with_step_request(
+        tx: oneshot::Sender<()>,
+        slot: Rc<Cell<Option<u32>>>,
+    ) -> Self {
+        Self {
+            tx: tx,
+            step_request: Some(slot),
+            restart_request: None,
+        }
+    }
+    /// Add a slot for restart requests (shared the same way as the slot for
+    /// step requests).
+    pub fn with_restart_request(self, slot: Rc<Cell<bool>>) -> Self {
+        Self {
+            restart_request: Some(slot),
+            ..self
+        }
     }
     /// Send a signal indicating that the user is done handling this event.
     #[async]
@@ -70,4 +176,91 @@ impl EventAck {
             unreachable!("{}: Unable to ack event", sc2_bug_tag())
         })
     }
+    /// Signal that the user is done with this event, and ask for the next
+    /// step to advance the game by the given number of game loops.
+    ///
+    /// This is meant for Step events, and only has an effect when the game is
+    /// stepped with an interval (the request replaces the interval for a
+    /// single step). Otherwise, it is the same as done.
+    #[async]
+    pub fn done_and_step(self, steps: u32) -> Result<()> {
+        if let Some(ref slot) = self.step_request {
+            let steps = match slot.get() {
+                Some(requested) => requested.min(steps),
+                None => steps,
+            };
+
+            slot.set(Some(steps.max(1)));
+        }
+
+        self.tx.send(()).map_err(|_| -> Error {
+            unreachable!("{}: Unable to ack event", sc2_bug_tag())
+        })
+    }
+    /// Signal that the user is done with this event, and ask for the game to
+    /// be restarted right away.
+    ///
+    /// This is meant for Step events of games that are restarted in place
+    /// (see MeleeBuilder::restart_forever). The episode ends without results
+    /// instead of being played out. Other games are left early.
+    #[async]
+    pub fn done_and_restart(self) -> Result<()> {
+        if let Some(ref slot) = self.restart_request {
+            slot.set(true);
+        }
+
+        self.tx.send(()).map_err(|_| -> Error {
+            unreachable!("{}: Unable to ack event", sc2_bug_tag())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_done_and_step() {
+        let slot = Rc::new(Cell::new(None));
+        let mut receivers = vec![];
+
+        let mut ack = || {
+            let (tx, rx) = oneshot::channel();
+            receivers.push(rx);
+
+            EventAck::wrap_with_step_request(tx, Rc::clone(&slot))
+        };
+
+        // plain acks leave the slot alone
+        ack().done().wait().unwrap();
+        assert_eq!(slot.get(), None);
+
+        ack().done_and_step(8).wait().unwrap();
+        assert_eq!(slot.get(), Some(8));
+
+        // the smallest request wins
+        ack().done_and_step(16).wait().unwrap();
+        ack().done_and_step(4).wait().unwrap();
+        ack().done().wait().unwrap();
+        assert_eq!(slot.get(), Some(4));
+
+        // the game needs to advance at least one game loop
+        ack().done_and_step(0).wait().unwrap();
+        assert_eq!(slot.get(), Some(1));
+    }
+    #[test]
+    fn test_done_and_restart() {
+        let steps = Rc::new(Cell::new(None));
+        let restart = Rc::new(Cell::new(false));
+        let (tx, _rx) = oneshot::channel();
+
+        EventAck::wrap_with_step_request(tx, Rc::clone(&steps))
+            .with_restart_request(Rc::clone(&restart))
+            .done_and_restart()
+            .wait()
+            .unwrap();
+
+        assert!(restart.get());
+        assert_eq!(steps.get(), None);
+    }
 }
@@ -1,15 +1,20 @@
+use std::cell::Cell;
 use std::mem;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use futures::future;
 use futures::prelude::*;
 use futures::unsync::{mpsc, oneshot};
 use sc2_proto::sc2api;
 use tokio_core::reactor;
+use tokio_timer::{wheel, Timer};
 use url::Url;
 
 use constants::sc2_bug_tag;
-use data::{GameSetup, Map, PlayerSetup, Race};
+use data::{GameSetup, Map, PlayerResult, PlayerSetup, Race};
 use launcher::GamePorts;
-use observer::{Event, EventAck};
+use observer::{Event, EventAck, EventKind};
 use services::action_service::{
     ActionBuilder,
     ActionClient,
@@ -25,6 +30,9 @@ use services::observer_service::{
 };
 use {Error, ErrorKind, IntoProto, Result};
 
+/// Game loops per second on the Faster game speed (used by realtime games).
+const GAME_LOOPS_PER_SECOND: f64 = 22.4;
+
 /// Build an agent.
 pub struct AgentBuilder {
     client: Option<ProtoClientBuilder>,
@@ -33,7 +41,7 @@ pub struct AgentBuilder {
 
     race: Option<Race>,
 
-    event_tx: Option<mpsc::Sender<(Event, EventAck)>>,
+    subscribers: Vec<EventSubscriber>,
     event_rx: Option<mpsc::Receiver<(Event, EventAck)>>,
 }
 
@@ -44,18 +52,20 @@ impl AgentBuilder {
         let action = ActionBuilder::new().proto_client(client.add_client());
         let observer = ObserverBuilder::new().proto_client(client.add_client());
 
-        let (tx, rx) = mpsc::channel(10);
-
-        Self {
+        let mut builder = Self {
             client: Some(client),
             action: Some(action),
             observer: Some(observer),
 
             race: None,
 
-            event_tx: Some(tx),
-            event_rx: Some(rx),
-        }
+            subscribers: vec![],
+            event_rx: None,
+        };
+
+        builder.event_rx = Some(builder.add_event_subscriber(|_| true));
+
+        builder
     }
 
     /// Set the race of the player.
@@ -87,7 +97,8 @@ impl AgentBuilder {
     /// Take the stream of game events to listen for.
     ///
     /// This should be called only once per builder! Subsequent calls will
-    /// return None because Streams should not be shared.
+    /// return None because Streams should not be shared. If more than one
+    /// consumer needs the events, use add_event_subscriber instead.
     ///
     /// The stream item is a tuple containing the event, and a promise to be
     /// fulfilled once the user is done with the event.
@@ -96,6 +107,39 @@ impl AgentBuilder {
     ) -> Option<mpsc::Receiver<(Event, EventAck)>> {
         mem::replace(&mut self.event_rx, None)
     }
+
+    /// Add an independent stream of the game events that pass the filter.
+    ///
+    /// Each event is dispatched to every subscriber whose filter accepts it,
+    /// and the agent waits until all of them have acknowledged the event
+    /// before moving on. Events rejected by the filter are never sent, so
+    /// they do not need to be acknowledged.
+    pub fn add_event_subscriber<F>(
+        &mut self,
+        filter: F,
+    ) -> mpsc::Receiver<(Event, EventAck)>
+    where
+        F: Fn(&Event) -> bool + 'static,
+    {
+        let (tx, rx) = mpsc::channel(10);
+
+        self.subscribers.push(EventSubscriber {
+            filter: Rc::new(filter),
+            tx: tx,
+        });
+
+        rx
+    }
+
+    /// Add an independent stream of the game events with the given kinds.
+    pub fn add_event_kind_subscriber(
+        &mut self,
+        kinds: &[EventKind],
+    ) -> mpsc::Receiver<(Event, EventAck)> {
+        let kinds = kinds.to_vec();
+
+        self.add_event_subscriber(move |e| kinds.contains(&e.get_kind()))
+    }
 }
 
 impl MeleeCompetitor for AgentBuilder {
@@ -104,7 +148,7 @@ impl MeleeCompetitor for AgentBuilder {
         handle: &reactor::Handle,
         rx: mpsc::Receiver<MeleeRequest>,
     ) -> Result<()> {
-        let tx = mem::replace(&mut self.event_tx, None).unwrap();
+        let subscribers = mem::replace(&mut self.subscribers, vec![]);
 
         let agent = Agent::new(
             self.client.as_ref().unwrap().add_client(),
@@ -117,7 +161,7 @@ impl MeleeCompetitor for AgentBuilder {
                 .unwrap()
                 .add_control_client(),
             rx,
-            tx,
+            subscribers,
             mem::replace(&mut self.race, None).unwrap_or(Race::Random),
         );
 
@@ -151,7 +195,7 @@ impl Agent {
         action: ActionControlClient,
         observer: ObserverControlClient,
         control_rx: mpsc::Receiver<MeleeRequest>,
-        events: mpsc::Sender<(Event, EventAck)>,
+        subscribers: Vec<EventSubscriber>,
         race: Race,
     ) -> Agent {
         Self {
@@ -159,7 +203,7 @@ impl Agent {
             client: client,
             observer: observer,
             agent: AgentTerminal {
-                tx: events,
+                subscribers: Rc::from(subscribers),
                 race: race,
             },
             action: action,
@@ -214,11 +258,20 @@ impl Agent {
                     })?;
                 },
                 MeleeRequest::RunGame(update_scheme, tx) => {
-                    await!(self.run_game(update_scheme))?;
-                    tx.send(()).map_err(|_| -> Error {
+                    let results = await!(self.run_game(update_scheme))?;
+                    tx.send(results).map_err(|_| -> Error {
                         unreachable!("{}: Unable to run game", sc2_bug_tag())
                     })?;
                 },
+                MeleeRequest::RestartGame(tx) => {
+                    await!(self.restart_game())?;
+                    tx.send(()).map_err(|_| -> Error {
+                        unreachable!(
+                            "{}: Unable to restart game",
+                            sc2_bug_tag()
+                        )
+                    })?;
+                },
                 MeleeRequest::LeaveGame(tx) => {
                     await!(self.leave_game())?;
                     tx.send(()).map_err(|_| -> Error {
@@ -240,7 +293,7 @@ impl Agent {
 
     fn get_player_setup(
         &self,
-        game: GameSetup,
+        game: Option<GameSetup>,
     ) -> impl Future<Item = PlayerSetup, Error = Error> {
         let future = self.agent.get_player_setup(game);
 
@@ -289,14 +342,15 @@ impl Agent {
                 },
             };
 
-            for player in players {
+            for (i, player) in players.into_iter().enumerate() {
                 let mut setup = sc2api::PlayerSetup::new();
 
                 match player {
-                    PlayerSetup::Computer(race, difficulty) => {
+                    PlayerSetup::Computer(race, difficulty, build) => {
                         setup.set_field_type(sc2api::PlayerType::Computer);
 
                         setup.set_difficulty(difficulty.to_proto());
+                        setup.set_ai_build(build.to_proto());
                         setup.set_race(race.into_proto()?);
                     },
                     PlayerSetup::Player(race) => {
@@ -308,11 +362,25 @@ impl Agent {
                     }*/
                 }
 
+                match settings.get_player_name(i) {
+                    Some(name) if !name.is_empty() => {
+                        setup.set_player_name(name.to_string());
+                    },
+                    _ => (),
+                }
+
                 req.mut_create_game()
                     .mut_player_setup()
                     .push(setup);
             }
 
+            if let Some(seed) = settings.get_random_seed() {
+                req.mut_create_game().set_random_seed(seed);
+            }
+
+            req.mut_create_game()
+                .set_disable_fog(settings.is_fog_disabled());
+
             match update_scheme {
                 UpdateScheme::Realtime => {
                     req.mut_create_game().set_realtime(true);
@@ -340,7 +408,7 @@ impl Agent {
             let mut req = sc2api::Request::new();
 
             match setup {
-                PlayerSetup::Computer(race, _) => {
+                PlayerSetup::Computer(race, _, _) => {
                     req.mut_join_game().set_race(race.into_proto()?);
                 },
                 PlayerSetup::Player(race) => {
@@ -390,7 +458,7 @@ impl Agent {
     fn run_game(
         &self,
         update_scheme: UpdateScheme,
-    ) -> impl Future<Item = (), Error = Error> {
+    ) -> impl Future<Item = Vec<PlayerResult>, Error = Error> {
         let observer = self.observer.clone();
         let agent = self.agent.clone();
         let action = self.action.clone();
@@ -410,35 +478,91 @@ impl Agent {
                 await!(agent.clone().handle_event(e))?;
             }
 
+            // only realtime games need to be paced
+            let timer: Option<Timer> = match update_scheme {
+                UpdateScheme::Realtime => Some(
+                    wheel().tick_duration(Duration::from_millis(1)).build(),
+                ),
+                UpdateScheme::Interval(_) => None,
+            };
+            let loop_budget =
+                Duration::new(0, (1e9 / GAME_LOOPS_PER_SECOND) as u32);
+
+            let mut step_request = None;
+
             loop {
+                let step_start = Instant::now();
+
                 match update_scheme {
                     UpdateScheme::Realtime => (),
                     UpdateScheme::Interval(interval) => {
                         let mut req = sc2api::Request::new();
-                        req.mut_step().set_count(interval);
+                        req.mut_step()
+                            .set_count(step_request.unwrap_or(interval));
 
                         await!(client.clone().request(req))?;
                     },
                 }
 
-                let (events, game_ended) = await!(observer.clone().step())?;
+                let (events, results) = await!(observer.clone().step())?;
 
                 for e in events {
                     await!(agent.clone().handle_event(e))?;
                 }
 
-                await!(agent.clone().handle_event(Event::Step))?;
+                let (steps, restart) =
+                    await!(agent.clone().handle_event(Event::Step))?;
+
+                step_request = steps;
 
-                if game_ended {
+                // a restart ends the episode early, without any results
+                if results.is_some() || restart {
                     await!(
                         agent
                             .clone()
                             .handle_event(Event::GameEnded)
                     )?;
-                    break;
+                    break Ok(results.unwrap_or_default());
                 }
 
                 await!(action.clone().step())?;
+
+                if let Some(timer) = timer.clone() {
+                    let elapsed = step_start.elapsed();
+
+                    if elapsed > loop_budget {
+                        await!(agent.clone().handle_event(
+                            Event::StepOverrun(elapsed - loop_budget)
+                        ))?;
+                    } else {
+                        // no point in observing the same game loop again
+                        await!(
+                            timer
+                                .sleep(loop_budget - elapsed)
+                                .map_err(|e| -> Error { e.into() })
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    fn restart_game(&self) -> impl Future<Item = (), Error = Error> {
+        let mut req = sc2api::Request::new();
+        req.mut_restart_game();
+
+        let future = self.client.request(req);
+
+        async_block! {
+            let rsp = await!(future)?;
+            let restart = rsp.get_restart_game();
+
+            if restart.has_error() {
+                bail!(ErrorKind::GameErrors(vec![format!(
+                    "{:?} - {}",
+                    restart.get_error(),
+                    restart.get_error_details()
+                )]))
             }
 
             Ok(())
@@ -467,16 +591,22 @@ impl Agent {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct AgentTerminal {
+#[derive(Clone)]
+struct EventSubscriber {
+    filter: Rc<Fn(&Event) -> bool>,
     tx: mpsc::Sender<(Event, EventAck)>,
+}
+
+#[derive(Clone)]
+pub struct AgentTerminal {
+    subscribers: Rc<Vec<EventSubscriber>>,
     race: Race,
 }
 
 impl AgentTerminal {
     fn get_player_setup(
         &self,
-        _game: GameSetup,
+        _game: Option<GameSetup>,
     ) -> impl Future<Item = PlayerSetup, Error = Error> {
         let race = self.race;
 
@@ -485,33 +615,146 @@ impl AgentTerminal {
         }
     }
 
+    /// Dispatch the event to the subscribers and wait until they are done.
+    ///
+    /// Returns the smallest step requested by any of them, and whether any
+    /// of them asked for a restart.
     fn handle_event(
         &self,
         event: Event,
-    ) -> impl Future<Item = (), Error = Error> {
-        let (tx, rx) = oneshot::channel();
-        let sender = self.tx.clone();
+    ) -> impl Future<Item = (Option<u32>, bool), Error = Error> {
+        let senders = self.subscribers
+            .iter()
+            .filter(|s| (s.filter)(&event))
+            .map(|s| s.tx.clone())
+            .collect::<Vec<_>>();
 
         async_block! {
-            if let Err(_) = await!(
-                sender
-                    .send((event, EventAck::wrap(tx)))
-            ) {
-                // This is not really an error, it just means that the user's
-                // event stream has been closed or dropped. For now I'm just
-                // dropping the event and continuing.
-                //
-                // It might be worth adding a warning for this later.
-            }
+            let step_request = Rc::new(Cell::new(None));
+            let restart_request = Rc::new(Cell::new(false));
+            let mut acks = vec![];
+
+            for sender in senders {
+                let (tx, rx) = oneshot::channel();
+                let ack = EventAck::wrap_with_step_request(
+                    tx,
+                    Rc::clone(&step_request),
+                ).with_restart_request(Rc::clone(&restart_request));
+
+                if let Err(_) = await!(sender.send((event.clone(), ack))) {
+                    // This is not really an error, it just means that the
+                    // user's event stream has been closed or dropped. For now
+                    // I'm just dropping the event and continuing.
+                    //
+                    // It might be worth adding a warning for this later.
+                    continue;
+                }
 
-            if let Err(_) = await!(rx) {
-                // ACK went out of scope, we can assume this means they are done
-                // using the event.
-                //
-                // It might be worth adding a warning for this later.
+                // If the ACK went out of scope, we can assume this means they
+                // are done using the event.
+                acks.push(rx.then(|_| -> Result<()> { Ok(()) }));
             }
 
-            Ok(())
+            // let the subscribers handle the event concurrently, but don't
+            // move on until all of them are done with it.
+            await!(future::join_all(acks))?;
+
+            Ok((step_request.get(), restart_request.get()))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    /// Let the event loop run for a bit.
+    fn turn(core: &mut reactor::Core) {
+        for _ in 0..10 {
+            core.turn(Some(Duration::from_millis(1)));
+        }
+    }
+
+    #[test]
+    fn test_handle_event() {
+        let mut core = reactor::Core::new().unwrap();
+        let mut builder = AgentBuilder::new();
+
+        // nobody listens to the default stream
+        builder.take_event_stream();
+
+        let all = builder.add_event_kind_subscriber(&[
+            EventKind::GameStarted,
+            EventKind::Step,
+        ]);
+        let steps = builder.add_event_subscriber(|e| match *e {
+            Event::Step => true,
+            _ => false,
+        });
+
+        let subscribers = mem::replace(&mut builder.subscribers, vec![]);
+        let terminal = AgentTerminal {
+            subscribers: Rc::from(subscribers),
+            race: Race::Terran,
+        };
+
+        // the first subscriber acks everything right away
+        let seen = Rc::new(RefCell::new(vec![]));
+        let all_seen = Rc::clone(&seen);
+
+        core.handle().spawn(all.for_each(move |(e, ack)| {
+            all_seen.borrow_mut().push(e.get_kind());
+
+            let done: Box<Future<Item = (), Error = Error>> = match e {
+                Event::Step => Box::new(ack.done_and_step(8)),
+                _ => Box::new(ack.done()),
+            };
+
+            done.map_err(|_| ())
+        }));
+
+        // the second one holds on to its acks
+        let held = Rc::new(RefCell::new(vec![]));
+        let steps_held = Rc::clone(&held);
+
+        core.handle().spawn(steps.for_each(move |(_, ack)| {
+            steps_held.borrow_mut().push(ack);
+            Ok(())
+        }));
+
+        assert_eq!(
+            core.run(terminal.handle_event(Event::GameStarted)).unwrap(),
+            (None, false)
+        );
+        assert_eq!(*seen.borrow(), vec![EventKind::GameStarted]);
+        assert!(held.borrow().is_empty());
+
+        let result = Rc::new(Cell::new(None));
+        let step_result = Rc::clone(&result);
+
+        core.handle().spawn(
+            terminal
+                .handle_event(Event::Step)
+                .map(move |r| step_result.set(Some(r)))
+                .map_err(|_| ()),
+        );
+        turn(&mut core);
+
+        // both got the step, but one of them is not done yet
+        assert_eq!(
+            *seen.borrow(),
+            vec![EventKind::GameStarted, EventKind::Step]
+        );
+        assert_eq!(held.borrow().len(), 1);
+        assert_eq!(result.get(), None);
+
+        let ack = held.borrow_mut().remove(0);
+        core.run(ack.done_and_step(4)).unwrap();
+        turn(&mut core);
+
+        // the smallest step requested by either of them wins
+        assert_eq!(result.get(), Some((Some(4), false)));
+    }
+}
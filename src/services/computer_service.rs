@@ -50,13 +50,22 @@ impl ComputerService {
                     })?;
                 },
                 MeleeRequest::RunGame(_, tx) => {
-                    tx.send(()).map_err(|_| -> Error {
+                    // the results are reported by the player
+                    tx.send(vec![]).map_err(|_| -> Error {
                         unreachable!(
                             "{}: Unable to ack run game",
                             sc2_bug_tag()
                         )
                     })?;
                 },
+                MeleeRequest::RestartGame(tx) => {
+                    tx.send(()).map_err(|_| -> Error {
+                        unreachable!(
+                            "{}: Unable to ack restart game",
+                            sc2_bug_tag()
+                        )
+                    })?;
+                },
                 MeleeRequest::LeaveGame(tx) => {
                     tx.send(()).map_err(|_| -> Error {
                         unreachable!(
@@ -0,0 +1,360 @@
+use std::env;
+use std::u16;
+
+use futures::prelude::*;
+use futures::unsync::mpsc;
+use tokio_core::reactor;
+use url::Url;
+
+use launcher::GamePorts;
+use services::melee_service::{MeleeClient, MeleeCompetitor, UpdateScheme};
+use {Error, ErrorKind, Result};
+
+/// Standard command line arguments passed to bots by community ladders.
+///
+/// Ladder managers start each bot with `--GamePort`, `--StartPort`,
+/// `--LadderServer` and `--OpponentId` (and sometimes `--RealTime`). Both
+/// `--Flag value` and `--Flag=value` are accepted, and any other arguments
+/// are ignored so that bots can mix in their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderArgs {
+    game_port: u16,
+    start_port: u16,
+    ladder_server: String,
+    opponent_id: Option<String>,
+    realtime: bool,
+}
+
+impl LadderArgs {
+    /// Parse the arguments of the current process.
+    pub fn from_env() -> Result<Self> {
+        Self::parse(env::args().skip(1))
+    }
+
+    /// Parse the ladder arguments out of a list of arguments.
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut game_port = None;
+        let mut start_port = None;
+        let mut ladder_server = None;
+        let mut opponent_id = None;
+        let mut realtime = false;
+
+        let mut args = args.into_iter().map(|arg| arg.as_ref().to_string());
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.find('=') {
+                Some(i) => {
+                    (arg[..i].to_string(), Some(arg[i + 1..].to_string()))
+                },
+                None => (arg.clone(), None),
+            };
+
+            if flag == "--RealTime" {
+                realtime = true;
+                continue;
+            }
+
+            match &flag[..] {
+                "--GamePort" | "--StartPort" | "--LadderServer"
+                | "--OpponentId" => (),
+                _ => continue,
+            }
+
+            let value = match inline {
+                Some(value) => value,
+                None => match args.next() {
+                    Some(value) => value,
+                    None => bail!(ErrorKind::InvalidArgument(format!(
+                        "{} needs a value",
+                        flag
+                    ))),
+                },
+            };
+
+            match &flag[..] {
+                "--GamePort" => game_port = Some(parse_port(&flag, &value)?),
+                "--StartPort" => {
+                    let port = parse_port(&flag, &value)?;
+
+                    // the game uses the 5 ports that follow the start port
+                    if port > u16::MAX - 5 {
+                        bail!(ErrorKind::InvalidArgument(format!(
+                            "{} leaves too few ports, got {}",
+                            flag, port
+                        )))
+                    }
+
+                    start_port = Some(port);
+                },
+                "--LadderServer" => ladder_server = Some(value),
+                "--OpponentId" => opponent_id = Some(value),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(Self {
+            game_port: match game_port {
+                Some(port) => port,
+                None => bail!(ErrorKind::MissingRequirement(
+                    "ladder needs --GamePort".to_string()
+                )),
+            },
+            start_port: match start_port {
+                Some(port) => port,
+                None => bail!(ErrorKind::MissingRequirement(
+                    "ladder needs --StartPort".to_string()
+                )),
+            },
+            ladder_server: ladder_server
+                .unwrap_or_else(|| "127.0.0.1".to_string()),
+            opponent_id: opponent_id,
+            realtime: realtime,
+        })
+    }
+
+    /// Port of the game instance that the bot should connect to.
+    pub fn get_game_port(&self) -> u16 {
+        self.game_port
+    }
+    /// First port of the range reserved for the game.
+    pub fn get_start_port(&self) -> u16 {
+        self.start_port
+    }
+    /// Address of the machine that hosts the game instance.
+    pub fn get_ladder_server(&self) -> &str {
+        &self.ladder_server
+    }
+    /// Identifier of the opponent (if the ladder provided one).
+    pub fn get_opponent_id(&self) -> Option<&str> {
+        self.opponent_id.as_ref().map(|id| &id[..])
+    }
+    /// Whether the game runs in real time.
+    pub fn is_realtime(&self) -> bool {
+        self.realtime
+    }
+
+    /// Address of the game instance.
+    fn get_url(&self) -> Result<Url> {
+        Ok(Url::parse(&format!(
+            "ws://{}:{}/sc2api",
+            self.ladder_server, self.game_port
+        ))?)
+    }
+
+    /// Ports of the game, laid out the same way as the reference ladder
+    /// clients do it.
+    fn get_game_ports(&self) -> GamePorts {
+        GamePorts::from_start_port(self.start_port + 1, 1)
+    }
+}
+
+fn parse_port(flag: &str, value: &str) -> Result<u16> {
+    match value.parse() {
+        Ok(port) => Ok(port),
+        Err(_) => bail!(ErrorKind::InvalidArgument(format!(
+            "{} expects a port, got {:?}",
+            flag, value
+        ))),
+    }
+}
+
+/// Build a coordinator that plays a single game on a ladder server.
+///
+/// Unlike the Melee coordinator, this does not launch any game instances. The
+/// ladder manager creates the game, and the agent only connects to the
+/// instance given in the LadderArgs, joins the game and plays it to the end.
+pub struct LadderBuilder {
+    player: Option<Box<MeleeCompetitor>>,
+
+    args: Option<LadderArgs>,
+    update_scheme: UpdateScheme,
+    handle: Option<reactor::Handle>,
+}
+
+impl LadderBuilder {
+    /// Start building a Ladder coordinator.
+    pub fn new() -> Self {
+        Self {
+            player: None,
+
+            args: None,
+            update_scheme: UpdateScheme::Interval(1),
+            handle: None,
+        }
+    }
+
+    /// The ladder arguments to connect with.
+    ///
+    /// This also switches to realtime updates if the ladder asked for them.
+    pub fn args(self, args: LadderArgs) -> Self {
+        Self {
+            update_scheme: if args.is_realtime() {
+                UpdateScheme::Realtime
+            } else {
+                self.update_scheme
+            },
+            args: Some(args),
+            ..self
+        }
+    }
+
+    /// Step the game instance with a discrete interval
+    pub fn step_interval(self, steps: u32) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Interval(steps),
+            ..self
+        }
+    }
+
+    /// Step the bot as fast as possible
+    pub fn step_realtime(self) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Realtime,
+            ..self
+        }
+    }
+
+    /// Set the player that joins the game.
+    pub fn player<T>(self, player: T) -> Self
+    where
+        T: MeleeCompetitor + Sized + 'static,
+    {
+        Self {
+            player: Some(Box::new(player)),
+            ..self
+        }
+    }
+
+    /// Provide a handle to spawn background tasks.
+    pub fn handle(self, handle: &reactor::Handle) -> Self {
+        Self {
+            handle: Some(handle.clone()),
+            ..self
+        }
+    }
+
+    /// Build the Ladder coordinator.
+    pub fn create(self) -> Result<Ladder> {
+        if self.player.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "LadderBuilder needs a player".to_string()
+            ))
+        } else if self.args.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "LadderBuilder needs LadderArgs".to_string()
+            ))
+        } else if self.handle.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "LadderBuilder needs a reactor handle".to_string()
+            ))
+        }
+
+        let handle = self.handle.unwrap();
+        let mut player = self.player.unwrap();
+
+        let (tx, rx) = mpsc::channel(10);
+
+        player.spawn(&handle, rx)?;
+
+        Ok(Ladder {
+            args: self.args.unwrap(),
+            update_scheme: self.update_scheme,
+            agent: MeleeClient::new(tx),
+        })
+    }
+}
+
+/// Plays a single game on a ladder server.
+pub struct Ladder {
+    args: LadderArgs,
+    update_scheme: UpdateScheme,
+    agent: MeleeClient,
+}
+
+impl IntoFuture for Ladder {
+    type Item = ();
+    type Error = Error;
+    type Future = Box<Future<Item = Self::Item, Error = Self::Error>>;
+
+    fn into_future(self) -> Self::Future {
+        Box::new(self.run())
+    }
+}
+
+impl Ladder {
+    #[async]
+    fn run(self) -> Result<()> {
+        let setup = await!(self.agent.clone().get_player_setup(None))?;
+
+        if setup.is_computer() {
+            bail!(ErrorKind::InvalidMatch(
+                "A built-in SC2 AI cannot join a ladder game".to_string()
+            ))
+        }
+
+        await!(self.agent.clone().connect(self.args.get_url()?))?;
+        await!(
+            self.agent
+                .clone()
+                .join_game(setup, Some(self.args.get_game_ports()))
+        )?;
+
+        await!(self.agent.clone().run_game(self.update_scheme))?;
+
+        await!(self.agent.clone().leave_game())?;
+        await!(self.agent.clone().disconnect())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let args = LadderArgs::parse(&[
+            "--GamePort",
+            "5677",
+            "--StartPort=5690",
+            "--LadderServer",
+            "10.0.0.2",
+            "--OpponentId",
+            "abc123",
+            "--MyOwnFlag",
+        ]).unwrap();
+
+        assert_eq!(args.get_game_port(), 5677);
+        assert_eq!(args.get_start_port(), 5690);
+        assert_eq!(args.get_ladder_server(), "10.0.0.2");
+        assert_eq!(args.get_opponent_id(), Some("abc123"));
+        assert!(!args.is_realtime());
+
+        let ports = args.get_game_ports();
+
+        assert_eq!(ports.shared_port, 5691);
+        assert_eq!(ports.server_ports.base_port, 5693);
+        assert_eq!(ports.client_ports[0].game_port, 5694);
+
+        assert!(LadderArgs::parse(&["--GamePort", "5677"]).is_err());
+        assert!(
+            LadderArgs::parse(&["--GamePort", "x", "--StartPort", "1"])
+                .is_err()
+        );
+
+        // the ports of the game must fit after the start port
+        assert!(
+            LadderArgs::parse(&["--GamePort", "1", "--StartPort", "65530"])
+                .is_ok()
+        );
+        assert!(
+            LadderArgs::parse(&["--GamePort", "1", "--StartPort", "65531"])
+                .is_err()
+        );
+    }
+}
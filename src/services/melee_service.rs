@@ -7,14 +7,19 @@ use tokio_core::reactor;
 use url::Url;
 
 use constants::{sc2_bug_tag, warning_tag};
-use data::{GameSetup, PlayerSetup};
+use data::{GameResult, GameSetup, PlayerResult, PlayerSetup};
+use instance::Instance;
 use launcher::{GamePorts, Launcher, LauncherSettings};
 use {Error, ErrorKind, Result};
 
 /// Update scheme for the agents to use.
 #[derive(Debug, Copy, Clone)]
 pub enum UpdateScheme {
-    /// Update as fast as possible.
+    /// Update in realtime.
+    ///
+    /// The agent observes the game at most once per game loop (22.4 loops
+    /// per second on the Faster speed), and is sent a StepOverrun event
+    /// whenever it takes longer than that to handle a step.
     Realtime,
     /// Step the game with a fixed interval.
     Interval(u32),
@@ -31,6 +36,7 @@ pub trait MeleeCompetitor {
 /// Build a Melee coordinator.
 pub struct MeleeBuilder {
     players: Vec<Box<MeleeCompetitor>>,
+    player_schemes: Vec<Option<UpdateScheme>>,
 
     launcher_settings: Option<LauncherSettings>,
     suite: Option<MeleeSuite>,
@@ -44,6 +50,7 @@ impl MeleeBuilder {
     pub fn new() -> Self {
         Self {
             players: vec![],
+            player_schemes: vec![],
 
             launcher_settings: None,
             suite: None,
@@ -69,7 +76,8 @@ impl MeleeBuilder {
         }
     }
 
-    /// Keep restarting game with the given settings.
+    /// Keep restarting game with the given settings (cycling through its
+    /// maps).
     pub fn repeat_forever(self, game: GameSetup) -> Self {
         Self {
             suite: Some(MeleeSuite::EndlessRepeat(game)),
@@ -77,6 +85,19 @@ impl MeleeBuilder {
         }
     }
 
+    /// Keep replaying a single player game with the given settings.
+    ///
+    /// Instead of leaving and creating a new game every time, the game is
+    /// restarted in place, which is much faster. This only works for a
+    /// player against a built-in AI. GameLoaded is only sent for the first
+    /// game, but GameStarted is sent for every restart.
+    pub fn restart_forever(self, game: GameSetup) -> Self {
+        Self {
+            suite: Some(MeleeSuite::EndlessRestart(game)),
+            ..self
+        }
+    }
+
     /// Step the game instance with a discrete interval
     pub fn step_interval(self, steps: u32) -> Self {
         Self {
@@ -110,6 +131,26 @@ impl MeleeBuilder {
         T: MeleeCompetitor + Sized + 'static,
     {
         self.players.push(Box::new(player));
+        self.player_schemes.push(None);
+        self
+    }
+
+    /// Add a player that steps with its own update scheme instead of the
+    /// one of the Melee coordinator.
+    ///
+    /// Both players of a PvP game can step with different intervals (the
+    /// game instances keep them in lockstep), but they cannot mix realtime
+    /// and stepped updates.
+    pub fn add_player_with_scheme<T>(
+        mut self,
+        player: T,
+        update_scheme: UpdateScheme,
+    ) -> Self
+    where
+        T: MeleeCompetitor + Sized + 'static,
+    {
+        self.players.push(Box::new(player));
+        self.player_schemes.push(Some(update_scheme));
         self
     }
 
@@ -144,16 +185,22 @@ impl MeleeBuilder {
         for mut player in self.players {
             let (tx, rx) = mpsc::channel(10);
 
-            melee_clients.push(MeleeClient { tx: tx });
+            melee_clients.push(MeleeClient::new(tx));
 
             player.spawn(&handle, rx)?;
         }
 
         assert!(melee_clients.len() == 2);
 
+        let update_scheme = self.update_scheme;
+        let update_schemes = [
+            self.player_schemes[0].unwrap_or(update_scheme),
+            self.player_schemes[1].unwrap_or(update_scheme),
+        ];
+
         Ok(Melee {
             suite: self.suite.unwrap(),
-            update_scheme: self.update_scheme,
+            update_schemes: update_schemes,
             launcher: Launcher::create(self.launcher_settings.unwrap())?,
             agents: melee_clients,
 
@@ -165,12 +212,13 @@ impl MeleeBuilder {
 enum MeleeSuite {
     OneAndDone(GameSetup),
     EndlessRepeat(GameSetup),
+    EndlessRestart(GameSetup),
 }
 
 pub struct Melee {
     suite: MeleeSuite,
     agents: Vec<MeleeClient>,
-    update_scheme: UpdateScheme,
+    update_schemes: [UpdateScheme; 2],
     launcher: Launcher,
 
     break_on_ctrlc: bool,
@@ -203,6 +251,8 @@ impl IntoFuture for Melee {
                         },
                     )
                 )?;
+            } else {
+                await!(self.run())?;
             }
             Ok(())
         })
@@ -211,11 +261,8 @@ impl IntoFuture for Melee {
 
 impl Melee {
     #[async]
-    fn run(mut self) -> Result<()> {
-        let instance1 = self.launcher.launch()?;
-        let mut maybe_instance2 = None;
-        let mut maybe_ports = None;
-
+    fn run(self) -> Result<()> {
+        let mut arena = Arena::new(self.launcher);
         let mut suite = Some(self.suite);
 
         while suite.is_some() {
@@ -225,152 +272,326 @@ impl Melee {
                     game
                 },
                 MeleeSuite::EndlessRepeat(game) => {
-                    suite = Some(MeleeSuite::EndlessRepeat(game.clone()));
+                    suite = Some(MeleeSuite::EndlessRepeat(game.next_map()));
                     game
                 },
+                MeleeSuite::EndlessRestart(game) => {
+                    await!(arena.play_restarts(
+                        self.agents[0].clone(),
+                        self.agents[1].clone(),
+                        game,
+                        self.update_schemes,
+                        None
+                    ))?;
+
+                    return Ok(());
+                },
             };
 
-            let player1 = await!(
-                self.agents[0]
+            let (next_arena, _) = await!(arena.play(
+                self.agents[0].clone(),
+                self.agents[1].clone(),
+                game,
+                self.update_schemes
+            ))?;
+
+            arena = next_arena;
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays games between pairs of competitors on local game instances.
+///
+/// The instances (and the ports of multiplayer games) are launched the first
+/// time they are needed and reused by every game after that.
+pub struct Arena {
+    launcher: Launcher,
+    instance1: Option<Instance>,
+    instance2: Option<Instance>,
+    ports: Option<GamePorts>,
+}
+
+impl Arena {
+    /// Create an arena that launches instances with the launcher.
+    pub fn new(launcher: Launcher) -> Self {
+        Self {
+            launcher: launcher,
+            instance1: None,
+            instance2: None,
+            ports: None,
+        }
+    }
+
+    /// Play one game and get the result of each competitor (in the order
+    /// they were given).
+    ///
+    /// Each competitor steps with its own update scheme, which must either
+    /// both be realtime or both be stepped in a PvP game.
+    #[async]
+    pub fn play(
+        mut self,
+        agent1: MeleeClient,
+        agent2: MeleeClient,
+        game: GameSetup,
+        update_schemes: [UpdateScheme; 2],
+    ) -> Result<(Self, [GameResult; 2])> {
+        let player1 = await!(
+            agent1
+                .clone()
+                .get_player_setup(Some(game.clone()))
+        )?;
+        let player2 = await!(
+            agent2
+                .clone()
+                .get_player_setup(Some(game.clone()))
+        )?;
+
+        let is_pvp = match (player1, player2) {
+            (PlayerSetup::Player(_), PlayerSetup::Player(_)) => true,
+            (PlayerSetup::Player(_), PlayerSetup::Computer(..)) => false,
+            (PlayerSetup::Computer(..), PlayerSetup::Player(_)) => false,
+            (PlayerSetup::Computer(..), PlayerSetup::Computer(..)) => {
+                bail!(ErrorKind::InvalidMatch(
+                    "A match between two built-in SC2 AI is not allowed"
+                        .to_string()
+                ))
+            },
+        };
+
+        if self.instance1.is_none() {
+            self.instance1 = Some(self.launcher.launch()?);
+        }
+
+        let url1 = self.instance1.as_ref().unwrap().get_url()?;
+
+        if is_pvp {
+            match (update_schemes[0], update_schemes[1]) {
+                (UpdateScheme::Realtime, UpdateScheme::Realtime) => (),
+                (UpdateScheme::Interval(_), UpdateScheme::Interval(_)) => (),
+                _ => bail!(ErrorKind::InvalidMatch(
+                    "Players cannot mix realtime and stepped updates"
+                        .to_string()
+                )),
+            }
+
+            if self.instance2.is_none() {
+                self.instance2 = Some(self.launcher.launch()?);
+            }
+
+            let url2 = self.instance2.as_ref().unwrap().get_url()?;
+
+            if self.ports.is_none() {
+                let mut ports = self.launcher.create_game_ports()?;
+
+                ports
+                    .client_ports
+                    .push(self.instance1.as_ref().unwrap().ports);
+                ports
+                    .client_ports
+                    .push(self.instance2.as_ref().unwrap().ports);
+
+                self.ports = Some(ports);
+            }
+
+            let ports = self.ports.clone().unwrap();
+
+            // connect to both at the same time
+            {
+                let connect1 = agent1.clone().connect(url1.clone());
+                let connect2 = agent2.clone().connect(url2.clone());
+
+                await!(connect1.join(connect2))?;
+            }
+
+            await!(agent1.clone().create_game(
+                game.clone(),
+                vec![player1, player2],
+                update_schemes[0]
+            ))?;
+
+            {
+                let join1 = agent1
                     .clone()
-                    .get_player_setup(game.clone())
-            )?;
-            let player2 = await!(
-                self.agents[1]
+                    .join_game(player1, Some(ports.clone()));
+                let join2 = agent2
                     .clone()
-                    .get_player_setup(game.clone())
-            )?;
-
-            let is_pvp = match (player1, player2) {
-                (PlayerSetup::Player(_), PlayerSetup::Player(_)) => true,
-                (PlayerSetup::Player(_), PlayerSetup::Computer(_, _)) => false,
-                (PlayerSetup::Computer(_, _), PlayerSetup::Player(_)) => false,
-                (PlayerSetup::Computer(_, _), PlayerSetup::Computer(_, _)) => {
-                    bail!(ErrorKind::InvalidMatch(
-                        "A match between two built-in SC2 AI is not allowed"
-                            .to_string()
-                    ))
-                },
+                    .join_game(player2, Some(ports.clone()));
+
+                await!(join1.join(join2))?;
+            }
+
+            let results = {
+                let run1 = agent1.clone().run_game(update_schemes[0]);
+                let run2 = agent2.clone().run_game(update_schemes[1]);
+
+                await!(run1.join(run2))?.0
             };
 
-            if is_pvp {
-                let instance2 = match maybe_instance2 {
-                    Some(instance) => instance,
-                    None => self.launcher.launch()?,
-                };
-
-                let ports = match maybe_ports {
-                    Some(ports) => ports,
-                    None => {
-                        let mut ports = self.launcher.create_game_ports();
-
-                        ports.client_ports.push(instance1.ports);
-                        ports.client_ports.push(instance2.ports);
-
-                        ports
-                    },
-                };
-
-                // connect to both at the same time
-                {
-                    let connect1 = self.agents[0]
-                        .clone()
-                        .connect(instance1.get_url()?);
-                    let connect2 = self.agents[1]
-                        .clone()
-                        .connect(instance2.get_url()?);
-
-                    await!(connect1.join(connect2))?;
-                }
-
-                await!(self.agents[0].clone().create_game(
-                    game.clone(),
-                    vec![player1, player2],
-                    self.update_scheme
-                ))?;
-
-                {
-                    let join1 = self.agents[0]
-                        .clone()
-                        .join_game(player1, Some(ports.clone()));
-                    let join2 = self.agents[1]
-                        .clone()
-                        .join_game(player2, Some(ports.clone()));
-
-                    await!(join1.join(join2))?;
-                }
-
-                {
-                    let run1 = self.agents[0]
-                        .clone()
-                        .run_game(self.update_scheme);
-                    let run2 = self.agents[1]
-                        .clone()
-                        .run_game(self.update_scheme);
-
-                    await!(run1.join(run2))?;
-                }
-
-                {
-                    let leave1 = self.agents[0].clone().leave_game();
-                    let leave2 = self.agents[1].clone().leave_game();
-
-                    await!(leave1.join(leave2))?;
-                }
-
-                {
-                    let disconnect1 = self.agents[0]
-                        .clone()
-                        .connect(instance1.get_url()?);
-                    let disconnect2 = self.agents[1]
-                        .clone()
-                        .connect(instance2.get_url()?);
-
-                    await!(disconnect1.join(disconnect2))?;
-                }
-
-                maybe_instance2 = Some(instance2);
-                maybe_ports = Some(ports);
+            {
+                let leave1 = agent1.clone().leave_game();
+                let leave2 = agent2.clone().leave_game();
+
+                await!(leave1.join(leave2))?;
+            }
+
+            {
+                let disconnect1 = agent1.clone().disconnect();
+                let disconnect2 = agent2.clone().disconnect();
+
+                await!(disconnect1.join(disconnect2))?;
+            }
+
+            // players get their ids in the order they were created
+            let outcome = [
+                get_result(&results, 1),
+                get_result(&results, 2),
+            ];
+
+            Ok((self, outcome))
+        } else {
+            let (player, computer, flipped) = if player1.is_computer() {
+                ((agent2.clone(), player2), player1, true)
+            } else if player2.is_computer() {
+                ((agent1.clone(), player1), player2, false)
             } else {
-                let (player, computer) = if player1.is_computer() {
-                    (
-                        (self.agents[1].clone(), player2),
-                        (self.agents[0].clone(), player1),
-                    )
-                } else if player2.is_computer() {
-                    (
-                        (self.agents[0].clone(), player1),
-                        (self.agents[1].clone(), player2),
-                    )
-                } else {
-                    unreachable!()
-                };
+                unreachable!()
+            };
+            let update_scheme = update_schemes[if flipped { 1 } else { 0 }];
+
+            assert!(player.1.is_player() && computer.is_computer());
+
+            // the names follow the order the players were added in, but the
+            // player is always created first
+            let game = if flipped {
+                swap_player_names(game)
+            } else {
+                game
+            };
+
+            await!(player.0.clone().connect(url1))?;
+            await!(player.0.clone().create_game(
+                game.clone(),
+                vec![player.1, computer],
+                update_scheme
+            ))?;
+            await!(player.0.clone().join_game(player.1, None))?;
+
+            let results = await!(player.0.clone().run_game(update_scheme))?;
+
+            await!(player.0.clone().leave_game())?;
+
+            await!(player.0.clone().disconnect())?;
+
+            // the player always comes before the computer
+            let outcome = if flipped {
+                [get_result(&results, 2), get_result(&results, 1)]
+            } else {
+                [get_result(&results, 1), get_result(&results, 2)]
+            };
+
+            Ok((self, outcome))
+        }
+    }
 
-                assert!(player.1.is_player() && computer.1.is_computer());
+    /// Play the same single player game several times (or forever if no
+    /// number of episodes is given), restarting it in place between games.
+    ///
+    /// An episode ends when the game is over, or as soon as the player asks
+    /// for a restart.
+    #[async]
+    pub fn play_restarts(
+        mut self,
+        agent1: MeleeClient,
+        agent2: MeleeClient,
+        game: GameSetup,
+        update_schemes: [UpdateScheme; 2],
+        episodes: Option<u32>,
+    ) -> Result<Self> {
+        let player1 = await!(
+            agent1
+                .clone()
+                .get_player_setup(Some(game.clone()))
+        )?;
+        let player2 = await!(
+            agent2
+                .clone()
+                .get_player_setup(Some(game.clone()))
+        )?;
 
-                await!(player.0.clone().connect(instance1.get_url()?))?;
-                await!(player.0.clone().create_game(
-                    game.clone(),
-                    vec![player.1, computer.1],
-                    self.update_scheme
-                ))?;
-                await!(player.0.clone().join_game(player.1, None))?;
+        let (player, computer, update_scheme) =
+            match (player1.is_computer(), player2.is_computer()) {
+                (false, true) => {
+                    ((agent1, player1), player2, update_schemes[0])
+                },
+                (true, false) => {
+                    ((agent2, player2), player1, update_schemes[1])
+                },
+                _ => bail!(ErrorKind::InvalidMatch(
+                    "Games can only be restarted with a single player"
+                        .to_string()
+                )),
+            };
+
+        if self.instance1.is_none() {
+            self.instance1 = Some(self.launcher.launch()?);
+        }
+
+        let url = self.instance1.as_ref().unwrap().get_url()?;
 
-                await!(player.0.clone().run_game(self.update_scheme))?;
+        await!(player.0.clone().connect(url))?;
+        await!(player.0.clone().create_game(
+            game,
+            vec![player.1, computer],
+            update_scheme
+        ))?;
+        await!(player.0.clone().join_game(player.1, None))?;
 
-                await!(player.0.clone().leave_game())?;
+        let mut episode = 0;
 
-                await!(player.0.clone().disconnect())?;
+        loop {
+            await!(player.0.clone().run_game(update_scheme))?;
+            episode += 1;
+
+            if episodes.map_or(false, |episodes| episode >= episodes) {
+                break;
             }
+
+            await!(player.0.clone().restart_game())?;
         }
 
-        Ok(())
+        await!(player.0.clone().leave_game())?;
+        await!(player.0.clone().disconnect())?;
+
+        Ok(self)
     }
 }
 
+/// The same setup with the names of the two players swapped.
+///
+/// Missing names become empty, so that the other name still goes to the
+/// right player.
+fn swap_player_names(game: GameSetup) -> GameSetup {
+    let mut names = (0..2)
+        .map(|i| game.get_player_name(i).unwrap_or("").to_string())
+        .collect::<Vec<_>>();
+
+    names.swap(0, 1);
+    game.player_names(names)
+}
+
+/// Result of the player with the given id (Undecided if it is missing).
+fn get_result(results: &[PlayerResult], player_id: u32) -> GameResult {
+    results
+        .iter()
+        .find(|r| r.get_player_id() == player_id)
+        .map_or(GameResult::Undecided, |r| r.get_result())
+}
+
 #[derive(Debug)]
 pub enum MeleeRequest {
-    PlayerSetup(GameSetup, oneshot::Sender<PlayerSetup>),
+    PlayerSetup(Option<GameSetup>, oneshot::Sender<PlayerSetup>),
     Connect(Url, oneshot::Sender<()>),
 
     CreateGame(
@@ -384,7 +605,8 @@ pub enum MeleeRequest {
         Option<GamePorts>,
         oneshot::Sender<()>,
     ),
-    RunGame(UpdateScheme, oneshot::Sender<()>),
+    RunGame(UpdateScheme, oneshot::Sender<Vec<PlayerResult>>),
+    RestartGame(oneshot::Sender<()>),
     LeaveGame(oneshot::Sender<()>),
 
     Disconnect(oneshot::Sender<()>),
@@ -397,9 +619,20 @@ pub struct MeleeClient {
 }
 
 impl MeleeClient {
+    /// Wrap the sender of a competitor's requests.
+    pub fn new(tx: mpsc::Sender<MeleeRequest>) -> Self {
+        Self { tx: tx }
+    }
+
     /// Get a player setup from the agent.
+    ///
+    /// The game is None when joining a game that was created by someone else
+    /// (ie. a ladder server).
     #[async]
-    pub fn get_player_setup(self, game: GameSetup) -> Result<PlayerSetup> {
+    pub fn get_player_setup(
+        self,
+        game: Option<GameSetup>,
+    ) -> Result<PlayerSetup> {
         let (tx, rx) = oneshot::channel();
 
         await!(
@@ -495,9 +728,15 @@ impl MeleeClient {
         }))
     }
 
-    /// Run the game to completion.
+    /// Run the game to completion and get the result of each player.
+    ///
+    /// The game also stops early (with no results) when the player asks for
+    /// a restart with EventAck::done_and_restart.
     #[async]
-    pub fn run_game(self, update_scheme: UpdateScheme) -> Result<()> {
+    pub fn run_game(
+        self,
+        update_scheme: UpdateScheme,
+    ) -> Result<Vec<PlayerResult>> {
         let (tx, rx) = oneshot::channel();
 
         await!(
@@ -513,6 +752,24 @@ impl MeleeClient {
         }))
     }
 
+    /// Restart the current game without leaving it.
+    #[async]
+    pub fn restart_game(self) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+
+        await!(
+            self.tx
+                .send(MeleeRequest::RestartGame(tx))
+                .map_err(|_| -> Error {
+                    unreachable!("{}: Unable to restart game", sc2_bug_tag())
+                })
+        )?;
+
+        await!(rx.map_err(|_| -> Error {
+            unreachable!("{}: Unable to restart game", sc2_bug_tag())
+        }))
+    }
+
     #[async]
     pub fn leave_game(self) -> Result<()> {
         let (tx, rx) = oneshot::channel();
@@ -547,3 +804,32 @@ impl MeleeClient {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use data::Map;
+
+    fn game() -> GameSetup {
+        GameSetup::new(Map::BlizzardMap("Acropolis".to_string()))
+    }
+
+    #[test]
+    fn test_swap_player_names() {
+        let setup = swap_player_names(
+            game().player_names(vec!["computer".into(), "bot".into()]),
+        );
+
+        assert_eq!(setup.get_player_name(0), Some("bot"));
+        assert_eq!(setup.get_player_name(1), Some("computer"));
+
+        // only the computer has a name
+        let setup = swap_player_names(
+            game().player_names(vec!["computer".into()]),
+        );
+
+        assert_eq!(setup.get_player_name(0), Some(""));
+        assert_eq!(setup.get_player_name(1), Some("computer"));
+    }
+}
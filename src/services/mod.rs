@@ -8,5 +8,7 @@ pub mod action_service;
 pub mod agent_service;
 pub mod client_service;
 pub mod computer_service;
+pub mod ladder_service;
 pub mod melee_service;
+pub mod multiplayer_service;
 pub mod observer_service;
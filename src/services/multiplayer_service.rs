@@ -0,0 +1,399 @@
+use futures::prelude::*;
+use futures::unsync::mpsc;
+use tokio_core::reactor;
+use url::Url;
+
+use data::{GameSetup, PlayerSetup, Race};
+use instance::Instance;
+use launcher::{GamePorts, Launcher, LauncherSettings};
+use services::melee_service::{MeleeClient, MeleeCompetitor, UpdateScheme};
+use {Error, ErrorKind, Result};
+
+/// Where the game instance of a player comes from.
+enum Endpoint {
+    /// Launch a new instance.
+    Launch(LauncherSettings),
+    /// Connect to an instance that is already running.
+    Remote(Url),
+}
+
+impl Endpoint {
+    /// Get the address of the instance (and the instance itself if it was
+    /// launched, which has to stay alive until the game is over).
+    fn open(self) -> Result<(Url, Option<Instance>)> {
+        match self {
+            Endpoint::Launch(settings) => {
+                let instance = Launcher::create(settings)?.launch()?;
+
+                Ok((instance.get_url()?, Some(instance)))
+            },
+            Endpoint::Remote(url) => Ok((url, None)),
+        }
+    }
+}
+
+/// Build a coordinator that hosts a multiplayer game for another process.
+///
+/// The host creates the game and joins it, then waits for the other player
+/// to join from its own instance with a Join coordinator. Both sides must use
+/// the same GamePorts, and the host should be started first.
+pub struct HostBuilder {
+    player: Option<Box<MeleeCompetitor>>,
+
+    endpoint: Option<Endpoint>,
+    game: Option<GameSetup>,
+    ports: Option<GamePorts>,
+    opponent_race: Race,
+    update_scheme: UpdateScheme,
+    handle: Option<reactor::Handle>,
+}
+
+impl HostBuilder {
+    /// Start building a Host coordinator.
+    pub fn new() -> Self {
+        Self {
+            player: None,
+
+            endpoint: None,
+            game: None,
+            ports: None,
+            opponent_race: Race::Random,
+            update_scheme: UpdateScheme::Realtime,
+            handle: None,
+        }
+    }
+
+    /// Launch a game instance with the given settings.
+    pub fn launcher_settings(self, settings: LauncherSettings) -> Self {
+        Self {
+            endpoint: Some(Endpoint::Launch(settings)),
+            ..self
+        }
+    }
+
+    /// Use a game instance that is already running at the given address.
+    pub fn instance_url(self, url: Url) -> Self {
+        Self {
+            endpoint: Some(Endpoint::Remote(url)),
+            ..self
+        }
+    }
+
+    /// The game to create.
+    pub fn game(self, game: GameSetup) -> Self {
+        Self {
+            game: Some(game),
+            ..self
+        }
+    }
+
+    /// The ports that every player joins with.
+    pub fn ports(self, ports: GamePorts) -> Self {
+        Self {
+            ports: Some(ports),
+            ..self
+        }
+    }
+
+    /// The race of the player that joins the game (defaults to Random).
+    pub fn opponent_race(self, race: Race) -> Self {
+        Self {
+            opponent_race: race,
+            ..self
+        }
+    }
+
+    /// Step the game instance with a discrete interval
+    pub fn step_interval(self, steps: u32) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Interval(steps),
+            ..self
+        }
+    }
+
+    /// Step the bot as fast as possible
+    pub fn step_realtime(self) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Realtime,
+            ..self
+        }
+    }
+
+    /// Set the player that hosts the game.
+    pub fn player<T>(self, player: T) -> Self
+    where
+        T: MeleeCompetitor + Sized + 'static,
+    {
+        Self {
+            player: Some(Box::new(player)),
+            ..self
+        }
+    }
+
+    /// Provide a handle to spawn background tasks.
+    pub fn handle(self, handle: &reactor::Handle) -> Self {
+        Self {
+            handle: Some(handle.clone()),
+            ..self
+        }
+    }
+
+    /// Build the Host coordinator.
+    pub fn create(self) -> Result<Host> {
+        if self.game.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "HostBuilder needs a GameSetup".to_string()
+            ))
+        }
+
+        let game = self.game.unwrap();
+        let session = Session::create(
+            "HostBuilder",
+            self.player,
+            self.endpoint,
+            self.ports,
+            self.handle,
+        )?;
+
+        Ok(Host {
+            session: session,
+            game: game,
+            opponent_race: self.opponent_race,
+            update_scheme: self.update_scheme,
+        })
+    }
+}
+
+/// Hosts a multiplayer game.
+pub struct Host {
+    session: Session,
+    game: GameSetup,
+    opponent_race: Race,
+    update_scheme: UpdateScheme,
+}
+
+impl IntoFuture for Host {
+    type Item = ();
+    type Error = Error;
+    type Future = Box<Future<Item = Self::Item, Error = Self::Error>>;
+
+    fn into_future(self) -> Self::Future {
+        let opponent = PlayerSetup::Player(self.opponent_race);
+
+        Box::new(self.session.play(
+            Some((self.game, opponent)),
+            self.update_scheme,
+        ))
+    }
+}
+
+/// Build a coordinator that joins a multiplayer game hosted by another
+/// process.
+///
+/// The game is created by a Host coordinator (or any other client that
+/// speaks the SC2 API), this side only joins it with the same GamePorts.
+pub struct JoinBuilder {
+    player: Option<Box<MeleeCompetitor>>,
+
+    endpoint: Option<Endpoint>,
+    ports: Option<GamePorts>,
+    update_scheme: UpdateScheme,
+    handle: Option<reactor::Handle>,
+}
+
+impl JoinBuilder {
+    /// Start building a Join coordinator.
+    pub fn new() -> Self {
+        Self {
+            player: None,
+
+            endpoint: None,
+            ports: None,
+            update_scheme: UpdateScheme::Realtime,
+            handle: None,
+        }
+    }
+
+    /// Launch a game instance with the given settings.
+    pub fn launcher_settings(self, settings: LauncherSettings) -> Self {
+        Self {
+            endpoint: Some(Endpoint::Launch(settings)),
+            ..self
+        }
+    }
+
+    /// Use a game instance that is already running at the given address.
+    pub fn instance_url(self, url: Url) -> Self {
+        Self {
+            endpoint: Some(Endpoint::Remote(url)),
+            ..self
+        }
+    }
+
+    /// The ports that every player joins with (must match the host).
+    pub fn ports(self, ports: GamePorts) -> Self {
+        Self {
+            ports: Some(ports),
+            ..self
+        }
+    }
+
+    /// Step the game instance with a discrete interval
+    pub fn step_interval(self, steps: u32) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Interval(steps),
+            ..self
+        }
+    }
+
+    /// Step the bot as fast as possible
+    pub fn step_realtime(self) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Realtime,
+            ..self
+        }
+    }
+
+    /// Set the player that joins the game.
+    pub fn player<T>(self, player: T) -> Self
+    where
+        T: MeleeCompetitor + Sized + 'static,
+    {
+        Self {
+            player: Some(Box::new(player)),
+            ..self
+        }
+    }
+
+    /// Provide a handle to spawn background tasks.
+    pub fn handle(self, handle: &reactor::Handle) -> Self {
+        Self {
+            handle: Some(handle.clone()),
+            ..self
+        }
+    }
+
+    /// Build the Join coordinator.
+    pub fn create(self) -> Result<Join> {
+        Ok(Join {
+            session: Session::create(
+                "JoinBuilder",
+                self.player,
+                self.endpoint,
+                self.ports,
+                self.handle,
+            )?,
+            update_scheme: self.update_scheme,
+        })
+    }
+}
+
+/// Joins a multiplayer game.
+pub struct Join {
+    session: Session,
+    update_scheme: UpdateScheme,
+}
+
+impl IntoFuture for Join {
+    type Item = ();
+    type Error = Error;
+    type Future = Box<Future<Item = Self::Item, Error = Self::Error>>;
+
+    fn into_future(self) -> Self::Future {
+        Box::new(self.session.play(None, self.update_scheme))
+    }
+}
+
+/// The half of a multiplayer game that is played by this process.
+struct Session {
+    agent: MeleeClient,
+    endpoint: Endpoint,
+    ports: GamePorts,
+}
+
+impl Session {
+    fn create(
+        builder: &str,
+        player: Option<Box<MeleeCompetitor>>,
+        endpoint: Option<Endpoint>,
+        ports: Option<GamePorts>,
+        handle: Option<reactor::Handle>,
+    ) -> Result<Self> {
+        let mut player = match player {
+            Some(player) => player,
+            None => bail!(ErrorKind::MissingRequirement(format!(
+                "{} needs a player",
+                builder
+            ))),
+        };
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint,
+            None => bail!(ErrorKind::MissingRequirement(format!(
+                "{} needs LauncherSettings or an instance url",
+                builder
+            ))),
+        };
+        let ports = match ports {
+            Some(ports) => ports,
+            None => bail!(ErrorKind::MissingRequirement(format!(
+                "{} needs GamePorts",
+                builder
+            ))),
+        };
+        let handle = match handle {
+            Some(handle) => handle,
+            None => bail!(ErrorKind::MissingRequirement(format!(
+                "{} needs a reactor handle",
+                builder
+            ))),
+        };
+
+        let (tx, rx) = mpsc::channel(10);
+
+        player.spawn(&handle, rx)?;
+
+        Ok(Self {
+            agent: MeleeClient::new(tx),
+            endpoint: endpoint,
+            ports: ports,
+        })
+    }
+
+    /// Play the game to completion, creating it first if this is the host.
+    #[async]
+    fn play(
+        self,
+        host: Option<(GameSetup, PlayerSetup)>,
+        update_scheme: UpdateScheme,
+    ) -> Result<()> {
+        let agent = self.agent;
+        let (url, _instance) = self.endpoint.open()?;
+
+        let game = host.as_ref().map(|&(ref game, _)| game.clone());
+        let setup = await!(agent.clone().get_player_setup(game))?;
+
+        if setup.is_computer() {
+            bail!(ErrorKind::InvalidMatch(
+                "A built-in SC2 AI cannot join a multiplayer game".to_string()
+            ))
+        }
+
+        await!(agent.clone().connect(url))?;
+
+        if let Some((game, opponent)) = host {
+            await!(agent.clone().create_game(
+                game,
+                vec![setup, opponent],
+                update_scheme
+            ))?;
+        }
+
+        await!(agent.clone().join_game(setup, Some(self.ports)))?;
+        await!(agent.clone().run_game(update_scheme))?;
+
+        await!(agent.clone().leave_game())?;
+        await!(agent.clone().disconnect())?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::rc::Rc;
@@ -8,6 +9,7 @@ use sc2_proto::sc2api;
 use tokio_core::reactor;
 
 use action::Action;
+use analysis::{SpatialIndex, UnitFilter};
 use constants::sc2_bug_tag;
 use data::{
     Ability,
@@ -17,10 +19,14 @@ use data::{
     BuffData,
     DisplayType,
     Effect,
+    EffectData,
+    EffectId,
     ImageData,
     MapInfo,
+    PlayerResult,
     Point2,
     PowerSource,
+    Rect2,
     Score,
     Tag,
     Unit,
@@ -43,6 +49,7 @@ pub struct Observation {
     camera_pos: Point2,
 
     units: Vec<Rc<Unit>>,
+    remembered_units: Vec<Rc<Unit>>,
 
     power_sources: Vec<PowerSource>,
     effects: Vec<Effect>,
@@ -63,6 +70,9 @@ pub struct Observation {
     visibility: ImageData,
 
     score: Score,
+    player_results: Option<Vec<PlayerResult>>,
+
+    spatial_index: RefCell<Option<Rc<SpatialIndex>>>,
 }
 
 impl Observation {
@@ -70,7 +80,10 @@ impl Observation {
     pub fn get_player_id(&self) -> u32 {
         self.player_id
     }
-    /// The previous game step.
+    /// The game step of the previous observation.
+    ///
+    /// In realtime games, the game keeps running while the agent is busy, so
+    /// any loops between this and the current step were skipped.
     pub fn get_previous_step(&self) -> u32 {
         self.previous_step
     }
@@ -87,6 +100,14 @@ impl Observation {
     pub fn get_units(&self) -> &[Rc<Unit>] {
         &self.units
     }
+    /// Enemy units that were seen before, but are no longer observed.
+    ///
+    /// Each unit is a snapshot from the last step it was visible (see
+    /// Unit::get_last_seen_step), so its position and state may be outdated.
+    /// Units are forgotten once they are destroyed.
+    pub fn get_remembered_units(&self) -> &[Rc<Unit>] {
+        &self.remembered_units
+    }
 
     /// All power sources associated with the current player.
     pub fn get_power_sources(&self) -> &[PowerSource] {
@@ -155,6 +176,17 @@ impl Observation {
     pub fn get_score(&self) -> &Score {
         &self.score
     }
+    /// Whether the game ended with this observation.
+    pub fn is_game_over(&self) -> bool {
+        self.player_results.is_some()
+    }
+    /// The result of each player (empty until the game is over).
+    pub fn get_player_results(&self) -> &[PlayerResult] {
+        match self.player_results {
+            Some(ref results) => results,
+            None => &[],
+        }
+    }
 
     /// Filter all units based on a custom condition.
     pub fn filter_units<F>(&self, filter: F) -> Vec<Rc<Unit>>
@@ -167,6 +199,71 @@ impl Observation {
             .map(|u| Rc::clone(u))
             .collect()
     }
+    /// Filter remembered enemy units based on a custom condition.
+    pub fn filter_remembered_units<F>(&self, filter: F) -> Vec<Rc<Unit>>
+    where
+        F: Fn(&Unit) -> bool,
+    {
+        self.remembered_units
+            .iter()
+            .filter(|u| filter(u))
+            .map(|u| Rc::clone(u))
+            .collect()
+    }
+    /// Filter both live and remembered units based on a custom condition.
+    pub fn filter_known_units<F>(&self, filter: F) -> Vec<Rc<Unit>>
+    where
+        F: Fn(&Unit) -> bool,
+    {
+        self.units
+            .iter()
+            .chain(self.remembered_units.iter())
+            .filter(|u| filter(u))
+            .map(|u| Rc::clone(u))
+            .collect()
+    }
+
+    /// Spatial index over the units of this observation.
+    ///
+    /// The index is only built the first time it is needed, so observations
+    /// that are never queried do not pay for it.
+    pub fn get_spatial_index(&self) -> Rc<SpatialIndex> {
+        let mut index = self.spatial_index.borrow_mut();
+
+        if index.is_none() {
+            *index = Some(Rc::from(SpatialIndex::new(&self.units)));
+        }
+
+        Rc::clone(index.as_ref().unwrap())
+    }
+    /// Units within the radius of the center that pass the filter.
+    pub fn units_in_circle(
+        &self,
+        center: Point2,
+        radius: f32,
+        filter: &UnitFilter,
+    ) -> Vec<Rc<Unit>> {
+        self.get_spatial_index().units_in_circle(center, radius, filter)
+    }
+    /// Units within the rectangle that pass the filter.
+    pub fn units_in_rect(
+        &self,
+        rect: Rect2,
+        filter: &UnitFilter,
+    ) -> Vec<Rc<Unit>> {
+        self.get_spatial_index().units_in_rect(rect, filter)
+    }
+    /// Up to n units closest to the point that pass the filter, from the
+    /// closest to the farthest.
+    pub fn nearest_n(
+        &self,
+        point: Point2,
+        n: usize,
+        filter: &UnitFilter,
+    ) -> Vec<Rc<Unit>> {
+        self.get_spatial_index().nearest_n(point, n, filter)
+    }
+
     /// Check if the given point contains creep.
     pub fn sample_creep(&self, _: Point2) -> bool {
         unimplemented!("has creep")
@@ -246,16 +343,7 @@ struct ObserverService {
     client: ProtoClient,
     request_rx: Option<mpsc::Receiver<ObserverRequest>>,
 
-    previous_step: u32,
-    current_step: u32,
-    previous_units: HashMap<Tag, Rc<Unit>>,
-    units: HashMap<Tag, Rc<Unit>>,
-
-    previous_upgrades: HashSet<Upgrade>,
-    upgrades: HashSet<Upgrade>,
-
-    actions: Vec<Action>,
-    // spatial_actions: Vec<SpatialAction>,
+    state: ObserverState,
 }
 
 impl ObserverService {
@@ -269,16 +357,7 @@ impl ObserverService {
             client: client,
             request_rx: Some(request_rx),
 
-            previous_step: 0,
-            current_step: 0,
-            previous_units: HashMap::new(),
-            units: HashMap::new(),
-
-            previous_upgrades: HashSet::new(),
-            upgrades: HashSet::new(),
-
-            actions: vec![],
-            // spatial_actions: vec![],
+            state: ObserverState::new(),
         }
     }
 
@@ -299,6 +378,7 @@ impl ObserverService {
         let mut ability_data = None;
         let mut upgrade_data = None;
         let mut buff_data = None;
+        let mut effect_data = None;
 
         #[async]
         for req in queue.map_err(|_| -> Error { unreachable!() }) {
@@ -308,18 +388,20 @@ impl ObserverService {
                     map_info = None;
                     unit_data = None;
 
+                    self.state.reset();
+
                     tx.send(()).map_err(|_| -> Error {
                         unreachable!("{}: Unable to ack reset", sc2_bug_tag())
                     })?;
                 },
                 Either::Control(ObserverControlRequest::Step(tx)) => {
-                    let (observer, new_observation, events, game_ended) =
+                    let (observer, new_observation, events, results) =
                         await!(self.get_observation())?;
 
                     self = observer;
                     observation = Some(new_observation);
 
-                    tx.send((events, game_ended))
+                    tx.send((events, results))
                         .map_err(|_| -> Error {
                             unreachable!(
                                 "{}: Unable to ack step",
@@ -358,7 +440,8 @@ impl ObserverService {
                 Either::Request(ObserverRequest::GetUnitData(_))
                 | Either::Request(ObserverRequest::GetAbilityData(_))
                 | Either::Request(ObserverRequest::GetUpgradeData(_))
-                | Either::Request(ObserverRequest::GetBuffData(_)) => {
+                | Either::Request(ObserverRequest::GetBuffData(_))
+                | Either::Request(ObserverRequest::GetEffectData(_)) => {
                     if unit_data.is_none() {
                         let (
                             observer,
@@ -366,6 +449,7 @@ impl ObserverService {
                             new_ability_data,
                             new_upgrade_data,
                             new_buff_data,
+                            new_effect_data,
                         ) = await!(self.get_game_data())?;
 
                         self = observer;
@@ -373,6 +457,7 @@ impl ObserverService {
                         ability_data = Some(new_ability_data);
                         upgrade_data = Some(new_upgrade_data);
                         buff_data = Some(new_buff_data);
+                        effect_data = Some(new_effect_data);
                     }
 
                     match req {
@@ -416,6 +501,17 @@ impl ObserverService {
                                     )
                                 })?;
                         },
+                        Either::Request(ObserverRequest::GetEffectData(
+                            tx,
+                        )) => {
+                            tx.send(Rc::clone(effect_data.as_ref().unwrap()))
+                                .map_err(|_| -> Error {
+                                    unreachable!(
+                                        "{}: Unable to return effect data",
+                                        sc2_bug_tag()
+                                    )
+                                })?;
+                        },
 
                         _ => unreachable!(),
                     }
@@ -429,13 +525,162 @@ impl ObserverService {
     #[async]
     fn get_observation(
         mut self,
-    ) -> Result<(Self, Rc<Observation>, Vec<Event>, bool)> {
+    ) -> Result<(
+        Self,
+        Rc<Observation>,
+        Vec<Event>,
+        Option<Vec<PlayerResult>>,
+    )> {
         let mut req = sc2api::Request::new();
         req.mut_observation();
 
+        let rsp = await!(self.client.clone().request(req))?;
+
+        let (observation, events, results) = self.state.update(rsp)?;
+
+        Ok((self, observation, events, results))
+    }
+
+    #[async]
+    fn get_map_info(self) -> Result<(Self, Rc<MapInfo>)> {
+        let mut req = sc2api::Request::new();
+        req.mut_game_info();
+
         let mut rsp = await!(self.client.clone().request(req))?;
 
-        let mut observation = rsp.take_observation().take_observation();
+        let info = Rc::from(MapInfo::from_proto(rsp.take_game_info())?);
+
+        Ok((self, info))
+    }
+
+    #[async]
+    fn get_game_data(
+        self,
+    ) -> Result<(
+        Self,
+        Rc<HashMap<UnitType, UnitTypeData>>,
+        Rc<HashMap<Ability, AbilityData>>,
+        Rc<HashMap<Upgrade, UpgradeData>>,
+        Rc<HashMap<Buff, BuffData>>,
+        Rc<HashMap<EffectId, EffectData>>,
+    )> {
+        let mut req = sc2api::Request::new();
+        req.mut_data().set_unit_type_id(true);
+        req.mut_data().set_ability_id(true);
+        req.mut_data().set_upgrade_id(true);
+        req.mut_data().set_buff_id(true);
+        req.mut_data().set_effect_id(true);
+
+        let mut rsp = await!(self.client.clone().request(req))?;
+
+        let mut unit_type_data = HashMap::new();
+        let mut ability_data = HashMap::new();
+        let mut upgrade_data = HashMap::new();
+        let mut buff_data = HashMap::new();
+        let mut effect_data = HashMap::new();
+
+        for data in rsp.mut_data().take_units().into_iter() {
+            let u = UnitTypeData::from_proto(data)?;
+
+            let unit_type = u.get_id();
+            unit_type_data.insert(unit_type, u);
+        }
+
+        for data in rsp.mut_data().take_abilities().into_iter() {
+            let a = AbilityData::from_proto(data)?;
+
+            let ability = a.get_id();
+            ability_data.insert(ability, a);
+        }
+
+        for data in rsp.mut_data().take_upgrades().into_iter() {
+            let u = UpgradeData::from_proto(data)?;
+
+            let upgrade = u.get_id();
+            upgrade_data.insert(upgrade, u);
+        }
+
+        for data in rsp.mut_data().take_buffs().into_iter() {
+            let b = BuffData::from_proto(data)?;
+
+            let buff = b.get_id();
+            buff_data.insert(buff, b);
+        }
+
+        for data in rsp.mut_data().take_effects().into_iter() {
+            let e = EffectData::from_proto(data)?;
+
+            let effect = e.get_id();
+            effect_data.insert(effect, e);
+        }
+
+        Ok((
+            self,
+            Rc::from(unit_type_data),
+            Rc::from(ability_data),
+            Rc::from(upgrade_data),
+            Rc::from(buff_data),
+            Rc::from(effect_data),
+        ))
+    }
+}
+
+/// Observe a single response with a fresh observer (used by the fixtures of
+/// other modules).
+#[cfg(test)]
+pub fn observe_response(rsp: sc2api::Response) -> Rc<Observation> {
+    ObserverState::new().update(rsp).unwrap().0
+}
+
+/// Game state that is tracked across steps in order to generate events.
+struct ObserverState {
+    previous_step: u32,
+    current_step: u32,
+    previous_units: HashMap<Tag, Rc<Unit>>,
+    units: HashMap<Tag, Rc<Unit>>,
+    remembered_units: HashMap<Tag, Rc<Unit>>,
+
+    previous_upgrades: HashSet<Upgrade>,
+    upgrades: HashSet<Upgrade>,
+
+    actions: Vec<Action>,
+    // spatial_actions: Vec<SpatialAction>,
+}
+
+impl ObserverState {
+    fn new() -> Self {
+        Self {
+            previous_step: 0,
+            current_step: 0,
+            previous_units: HashMap::new(),
+            units: HashMap::new(),
+            remembered_units: HashMap::new(),
+
+            previous_upgrades: HashSet::new(),
+            upgrades: HashSet::new(),
+
+            actions: vec![],
+            // spatial_actions: vec![],
+        }
+    }
+
+    /// Forget everything from the previous game.
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Process an observation response from the game instance.
+    ///
+    /// Returns the new observation, the events that occurred since the last
+    /// update, and the results of each player once the game has ended.
+    fn update(
+        &mut self,
+        mut rsp: sc2api::Response,
+    ) -> Result<(Rc<Observation>, Vec<Event>, Option<Vec<PlayerResult>>)>
+    {
+        let mut rsp_observation = rsp.take_observation();
+        let player_results = rsp_observation.take_player_result();
+        let mut observation = rsp_observation.take_observation();
 
         self.previous_step = self.current_step;
         self.current_step = observation.get_game_loop();
@@ -451,7 +696,18 @@ impl ObserverService {
                 Ok(mut unit) => {
                     let tag = unit.get_tag();
 
-                    unit.set_last_seen_step(self.current_step);
+                    if unit.get_display_type() == DisplayType::Snapshot {
+                        // snapshots are not actually seen this step, so keep
+                        // the step where we last saw the real thing.
+                        unit.set_last_seen_step(
+                            match self.remembered_units.get(&tag) {
+                                Some(u) => u.get_last_seen_step(),
+                                None => self.current_step,
+                            },
+                        );
+                    } else {
+                        unit.set_last_seen_step(self.current_step);
+                    }
 
                     self.units.insert(tag, Rc::from(unit));
                 },
@@ -459,6 +715,44 @@ impl ObserverService {
             }
         }
 
+        let mut events = vec![];
+        let mut dead_units = HashSet::new();
+
+        if raw.has_event() {
+            for tag in raw.get_event().get_dead_units() {
+                dead_units.insert(*tag);
+
+                // a unit can die in the same frame that it appears, so check
+                // the current units before falling back to older sightings.
+                let unit = match self.units.remove(tag) {
+                    Some(unit) => Some(unit),
+                    None => match self.previous_units.get(tag) {
+                        Some(unit) => Some(Rc::clone(unit)),
+                        None => self.remembered_units
+                            .get(tag)
+                            .map(|u| Rc::clone(u)),
+                    },
+                };
+
+                self.remembered_units.remove(tag);
+
+                if let Some(unit) = unit {
+                    let mut dead_unit = (*unit).clone();
+                    dead_unit.mark_dead();
+
+                    events.push(Event::UnitDestroyed(Rc::from(dead_unit)));
+                }
+            }
+        }
+
+        for (tag, unit) in &self.units {
+            if unit.get_alliance() == Alliance::Enemy
+                && unit.get_display_type() == DisplayType::Visible
+            {
+                self.remembered_units.insert(*tag, Rc::clone(unit));
+            }
+        }
+
         self.previous_upgrades =
             mem::replace(&mut self.upgrades, HashSet::new());
 
@@ -466,6 +760,18 @@ impl ObserverService {
             self.upgrades.insert(Upgrade::from_proto(u)?);
         }
 
+        let results = if rsp.get_status() != sc2api::Status::in_game {
+            let mut results = vec![];
+
+            for result in player_results.into_iter() {
+                results.push(PlayerResult::from_proto(result)?);
+            }
+
+            Some(results)
+        } else {
+            None
+        };
+
         let mut map_state = raw.take_map_state();
 
         let new_observation = Rc::from(Observation {
@@ -482,6 +788,11 @@ impl ObserverService {
                 .values()
                 .map(|u| Rc::clone(u))
                 .collect(),
+            remembered_units: self.remembered_units
+                .iter()
+                .filter(|&(tag, _)| !self.units.contains_key(tag))
+                .map(|(_, u)| Rc::clone(u))
+                .collect(),
             power_sources: {
                 let mut power_sources = vec![];
 
@@ -492,7 +803,15 @@ impl ObserverService {
                 power_sources
             },
             upgrades: self.upgrades.iter().map(|u| *u).collect(),
-            effects: vec![],
+            effects: {
+                let mut effects = vec![];
+
+                for e in raw.take_effects().into_iter() {
+                    effects.push(Effect::from_proto(e)?);
+                }
+
+                effects
+            },
 
             minerals: player_common.get_minerals(),
             vespene: player_common.get_vespene(),
@@ -509,6 +828,9 @@ impl ObserverService {
             visibility: map_state.take_visibility().into_sc2()?,
 
             score: observation.take_score().into_sc2()?,
+            player_results: results.clone(),
+
+            spatial_index: RefCell::new(None),
         });
 
         if is_new_frame {
@@ -556,21 +878,6 @@ impl ObserverService {
         //     }
         // }
 
-        let mut events = vec![];
-
-        if raw.has_event() {
-            let event = raw.get_event();
-
-            for tag in event.get_dead_units() {
-                match self.previous_units.get(tag) {
-                    Some(ref mut unit) => {
-                        events.push(Event::UnitDestroyed(Rc::clone(unit)));
-                    },
-                    None => (),
-                }
-            }
-        }
-
         for ref unit in self.units.values() {
             match self.previous_units.get(&unit.get_tag()) {
                 Some(ref prev_unit) => {
@@ -578,26 +885,85 @@ impl ObserverService {
                         && !prev_unit.get_orders().is_empty()
                     {
                         events.push(Event::UnitIdle(Rc::clone(unit)));
-                    } else if unit.get_build_progress() >= 1.0
+                    }
+
+                    if unit.get_build_progress() >= 1.0
                         && prev_unit.get_build_progress() < 1.0
                     {
                         events.push(Event::BuildingCompleted(Rc::clone(unit)));
                     }
-                },
-                None => {
-                    if unit.get_alliance() == Alliance::Enemy
-                        && unit.get_display_type() == DisplayType::Visible
+
+                    if unit.get_owner() != prev_unit.get_owner() {
+                        events.push(Event::UnitOwnerChanged(
+                            Rc::clone(unit),
+                            prev_unit.get_owner(),
+                        ));
+                    }
+
+                    if unit.get_unit_type() != prev_unit.get_unit_type() {
+                        events.push(Event::UnitTypeChanged(
+                            Rc::clone(unit),
+                            prev_unit.get_unit_type(),
+                        ));
+                    } else if unit.get_display_type() == DisplayType::Visible
+                        && prev_unit.get_display_type() == DisplayType::Visible
                     {
-                        events.push(Event::UnitDetected(Rc::clone(unit)));
-                    } else {
-                        events.push(Event::UnitCreated(Rc::clone(unit)));
+                        // health and shields are not set for snapshots, and
+                        // morphs can change the max health of a unit, so only
+                        // compare visible units of the same type.
+                        let damage = (prev_unit.get_health()
+                            + prev_unit.get_shield())
+                            - (unit.get_health() + unit.get_shield());
+
+                        if damage > 0.0 {
+                            events.push(Event::UnitDamaged(
+                                Rc::clone(unit),
+                                damage,
+                            ));
+                        }
                     }
 
-                    events.push(Event::UnitIdle(Rc::clone(unit)));
+                    if unit.get_display_type() == DisplayType::Snapshot
+                        && prev_unit.get_display_type() == DisplayType::Visible
+                    {
+                        events.push(Event::UnitEnteredFog(Rc::clone(unit)));
+                    }
+                },
+                None => match unit.get_alliance() {
+                    Alliance::Domestic => {
+                        events.push(Event::UnitCreated(Rc::clone(unit)));
+
+                        if unit.get_build_progress() < 1.0 {
+                            events.push(Event::ConstructionStarted(
+                                Rc::clone(unit),
+                            ));
+                        }
+
+                        if unit.get_orders().is_empty() {
+                            events.push(Event::UnitIdle(Rc::clone(unit)));
+                        }
+                    },
+                    Alliance::Enemy => {
+                        events.push(Event::UnitDetected(Rc::clone(unit)));
+                    },
+                    // allied and neutral units (such as minerals) are just
+                    // there, they are not interesting enough for an event.
+                    Alliance::Ally | Alliance::Neutral => (),
                 },
             }
         }
 
+        for (tag, prev_unit) in &self.previous_units {
+            // our own units can disappear into transports and refineries, so
+            // only the units of other players are considered lost in the fog.
+            if !self.units.contains_key(tag) && !dead_units.contains(tag)
+                && prev_unit.get_alliance() != Alliance::Domestic
+                && prev_unit.get_display_type() == DisplayType::Visible
+            {
+                events.push(Event::UnitEnteredFog(Rc::clone(prev_unit)));
+            }
+        }
+
         let prev_upgrades =
             mem::replace(&mut self.previous_upgrades, HashSet::new());
 
@@ -630,89 +996,14 @@ impl ObserverService {
             events.push(Event::NydusWormsDetected(nydus_worms));
         }
 
-        let game_ended = if rsp.get_status() != sc2api::Status::in_game {
-            true
-        } else {
-            false
-        };
-
-        Ok((self, new_observation, events, game_ended))
-    }
-
-    #[async]
-    fn get_map_info(self) -> Result<(Self, Rc<MapInfo>)> {
-        let mut req = sc2api::Request::new();
-        req.mut_game_info();
-
-        let mut rsp = await!(self.client.clone().request(req))?;
-
-        let info = Rc::from(MapInfo::from_proto(rsp.take_game_info())?);
-
-        Ok((self, info))
-    }
-
-    #[async]
-    fn get_game_data(
-        self,
-    ) -> Result<(
-        Self,
-        Rc<HashMap<UnitType, UnitTypeData>>,
-        Rc<HashMap<Ability, AbilityData>>,
-        Rc<HashMap<Upgrade, UpgradeData>>,
-        Rc<HashMap<Buff, BuffData>>,
-    )> {
-        let mut req = sc2api::Request::new();
-        req.mut_data().set_unit_type_id(true);
-
-        let mut rsp = await!(self.client.clone().request(req))?;
-
-        let mut unit_type_data = HashMap::new();
-        let mut ability_data = HashMap::new();
-        let mut upgrade_data = HashMap::new();
-        let mut buff_data = HashMap::new();
-
-        for data in rsp.mut_data().take_units().into_iter() {
-            let u = UnitTypeData::from_proto(data)?;
-
-            let unit_type = u.get_id();
-            unit_type_data.insert(unit_type, u);
-        }
-
-        for data in rsp.mut_data().take_abilities().into_iter() {
-            let a = AbilityData::from_proto(data)?;
-
-            let ability = a.get_id();
-            ability_data.insert(ability, a);
-        }
-
-        for data in rsp.mut_data().take_upgrades().into_iter() {
-            let u = UpgradeData::from_proto(data)?;
-
-            let upgrade = u.get_id();
-            upgrade_data.insert(upgrade, u);
-        }
-
-        for data in rsp.mut_data().take_buffs().into_iter() {
-            let b = BuffData::from_proto(data)?;
-
-            let buff = b.get_id();
-            buff_data.insert(buff, b);
-        }
-
-        Ok((
-            self,
-            Rc::from(unit_type_data),
-            Rc::from(ability_data),
-            Rc::from(upgrade_data),
-            Rc::from(buff_data),
-        ))
+        Ok((new_observation, events, results))
     }
 }
 
 #[derive(Debug)]
 enum ObserverControlRequest {
     Reset(oneshot::Sender<()>),
-    Step(oneshot::Sender<(Vec<Event>, bool)>),
+    Step(oneshot::Sender<(Vec<Event>, Option<Vec<PlayerResult>>)>),
 }
 
 #[derive(Debug)]
@@ -725,6 +1016,7 @@ enum ObserverRequest {
     GetAbilityData(oneshot::Sender<Rc<HashMap<Ability, AbilityData>>>),
     GetUpgradeData(oneshot::Sender<Rc<HashMap<Upgrade, UpgradeData>>>),
     GetBuffData(oneshot::Sender<Rc<HashMap<Buff, BuffData>>>),
+    GetEffectData(oneshot::Sender<Rc<HashMap<EffectId, EffectData>>>),
 }
 
 enum Either {
@@ -755,9 +1047,10 @@ impl ObserverControlClient {
         }))
     }
 
-    /// returns a list of game events that have occurred since last step
+    /// returns a list of game events that have occurred since last step and
+    /// the results of the game if it has ended
     #[async]
-    pub fn step(self) -> Result<(Vec<Event>, bool)> {
+    pub fn step(self) -> Result<(Vec<Event>, Option<Vec<PlayerResult>>)> {
         let (tx, rx) = oneshot::channel();
 
         await!(
@@ -918,4 +1211,255 @@ impl ObserverClient {
             }))
         }
     }
+
+    /// Get data about each effect.
+    pub fn get_effect_data(
+        &self,
+    ) -> impl Future<Item = Rc<HashMap<EffectId, EffectData>>, Error = Error>
+    {
+        let (tx, rx) = oneshot::channel();
+        let sender = self.tx.clone();
+
+        async_block! {
+            await!(
+                sender
+                    .send(ObserverRequest::GetEffectData(tx))
+                    .map(|_| ())
+                    .map_err(|_| -> Error {
+                        unreachable!(
+                            "{}: Unable to send effect data request",
+                            sc2_bug_tag()
+                        )
+                    })
+            )?;
+
+            await!(rx.map_err(|_| -> Error {
+                unreachable!("{}: Unable to recv effect data", sc2_bug_tag())
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::{common, raw};
+
+    use fixtures::{raw_unit, response};
+    use observer::EventKind;
+
+    fn unit(tag: Tag, alliance: raw::Alliance) -> raw::Unit {
+        raw_unit(tag, 45, alliance)
+    }
+
+    /// Kinds of the events that concern the given unit.
+    fn events_for(events: &[Event], tag: Tag) -> Vec<EventKind> {
+        events
+            .iter()
+            .filter(|e| match **e {
+                Event::UnitDestroyed(ref u)
+                | Event::UnitCreated(ref u)
+                | Event::UnitIdle(ref u)
+                | Event::UnitDetected(ref u)
+                | Event::UnitDamaged(ref u, _)
+                | Event::UnitTypeChanged(ref u, _)
+                | Event::UnitEnteredFog(ref u)
+                | Event::UnitOwnerChanged(ref u, _)
+                | Event::ConstructionStarted(ref u)
+                | Event::BuildingCompleted(ref u) => u.get_tag() == tag,
+                _ => false,
+            })
+            .map(|e| e.get_kind())
+            .collect()
+    }
+
+    #[test]
+    fn test_first_observation() {
+        let mut state = ObserverState::new();
+
+        let (_, events, _) = state
+            .update(response(
+                1,
+                vec![
+                    unit(1, raw::Alliance::Domestic),
+                    unit(2, raw::Alliance::Neutral),
+                    unit(3, raw::Alliance::Enemy),
+                ],
+                vec![],
+            ))
+            .unwrap();
+
+        assert_eq!(
+            events_for(&events, 1),
+            vec![EventKind::UnitCreated, EventKind::UnitIdle]
+        );
+        assert_eq!(events_for(&events, 2), vec![]);
+        assert_eq!(events_for(&events, 3), vec![EventKind::UnitDetected]);
+    }
+
+    #[test]
+    fn test_unit_destroyed() {
+        let mut state = ObserverState::new();
+
+        state
+            .update(response(1, vec![unit(1, raw::Alliance::Enemy)], vec![]))
+            .unwrap();
+        let (observation, events, _) =
+            state.update(response(2, vec![], vec![1])).unwrap();
+
+        assert_eq!(events.len(), 1);
+        match events[0] {
+            Event::UnitDestroyed(ref u) => {
+                assert_eq!(u.get_tag(), 1);
+                assert!(!u.is_alive());
+            },
+            _ => panic!("expected UnitDestroyed"),
+        }
+
+        assert!(observation.get_remembered_units().is_empty());
+    }
+
+    #[test]
+    fn test_unit_destroyed_on_arrival() {
+        let mut state = ObserverState::new();
+
+        state.update(response(1, vec![], vec![])).unwrap();
+        let (observation, events, _) = state
+            .update(response(
+                2,
+                vec![unit(1, raw::Alliance::Enemy)],
+                vec![1],
+            ))
+            .unwrap();
+
+        assert_eq!(events_for(&events, 1), vec![EventKind::UnitDestroyed]);
+        assert!(observation.get_units().is_empty());
+        assert!(observation.get_remembered_units().is_empty());
+    }
+
+    #[test]
+    fn test_unit_destroyed_in_fog() {
+        let mut state = ObserverState::new();
+
+        state
+            .update(response(1, vec![unit(1, raw::Alliance::Enemy)], vec![]))
+            .unwrap();
+        let (observation, events, _) =
+            state.update(response(2, vec![], vec![])).unwrap();
+
+        assert_eq!(events_for(&events, 1), vec![EventKind::UnitEnteredFog]);
+        assert_eq!(observation.get_remembered_units().len(), 1);
+
+        let (observation, events, _) =
+            state.update(response(3, vec![], vec![1])).unwrap();
+
+        assert_eq!(events_for(&events, 1), vec![EventKind::UnitDestroyed]);
+        assert!(observation.get_remembered_units().is_empty());
+    }
+
+    #[test]
+    fn test_unit_idle() {
+        let mut state = ObserverState::new();
+
+        let mut busy = unit(1, raw::Alliance::Domestic);
+        busy.mut_orders().push({
+            let mut order = raw::UnitOrder::new();
+            order.set_ability_id(1);
+            order
+        });
+
+        let (_, events, _) =
+            state.update(response(1, vec![busy], vec![])).unwrap();
+        assert_eq!(events_for(&events, 1), vec![EventKind::UnitCreated]);
+
+        let (_, events, _) = state
+            .update(response(
+                2,
+                vec![unit(1, raw::Alliance::Domestic)],
+                vec![],
+            ))
+            .unwrap();
+        assert_eq!(events_for(&events, 1), vec![EventKind::UnitIdle]);
+    }
+
+    #[test]
+    fn test_unit_damaged() {
+        let mut state = ObserverState::new();
+
+        state
+            .update(response(1, vec![unit(1, raw::Alliance::Enemy)], vec![]))
+            .unwrap();
+
+        let mut damaged = unit(1, raw::Alliance::Enemy);
+        damaged.set_health(40.0);
+
+        let (_, events, _) =
+            state.update(response(2, vec![damaged], vec![])).unwrap();
+
+        assert_eq!(events.len(), 1);
+        match events[0] {
+            Event::UnitDamaged(_, amount) => assert_eq!(amount, 5.0),
+            _ => panic!("expected UnitDamaged"),
+        }
+    }
+
+    #[test]
+    fn test_construction() {
+        let mut state = ObserverState::new();
+
+        let mut building = unit(1, raw::Alliance::Domestic);
+        building.set_unit_type(21);
+        building.set_build_progress(0.5);
+
+        let (_, events, _) =
+            state.update(response(1, vec![building], vec![])).unwrap();
+        assert_eq!(
+            events_for(&events, 1),
+            vec![
+                EventKind::UnitCreated,
+                EventKind::ConstructionStarted,
+                EventKind::UnitIdle,
+            ]
+        );
+
+        let mut building = unit(1, raw::Alliance::Domestic);
+        building.set_unit_type(21);
+
+        let (_, events, _) =
+            state.update(response(2, vec![building], vec![])).unwrap();
+        assert_eq!(
+            events_for(&events, 1),
+            vec![EventKind::BuildingCompleted]
+        );
+    }
+
+    #[test]
+    fn test_effects() {
+        let mut state = ObserverState::new();
+
+        let mut rsp = response(1, vec![], vec![]);
+        rsp.mut_observation()
+            .mut_observation()
+            .mut_raw_data()
+            .mut_effects()
+            .push({
+                let mut effect = raw::Effect::new();
+                effect.set_effect_id(1);
+                effect.mut_pos().push({
+                    let mut pos = common::Point2D::new();
+                    pos.set_x(10.0);
+                    pos.set_y(20.0);
+                    pos
+                });
+                effect
+            });
+
+        let (observation, _, _) = state.update(rsp).unwrap();
+        let effects = observation.get_effects();
+
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].get_id(), EffectId::PsiStormPersistent);
+        assert_eq!(effects[0].get_positions(), &[Point2::new(10.0, 20.0)]);
+    }
 }
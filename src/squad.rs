@@ -0,0 +1,297 @@
+//! Contains named groups of units that are kept up to date automatically.
+//!
+//! Most bots end up tracking groups of units (the main army, a harass
+//! squad, the workers at each base) and computing things like the center of
+//! each group by hand. The SquadManager keeps track of the tags in each
+//! group as units are created and destroyed, and each Squad can turn an
+//! ability into a single Action for the whole group.
+//!
+//! Like the BuildOrder, the SquadManager does not talk to the game by
+//! itself. Feed it the events from the agent with on_event, call update with
+//! each new observation, and send the actions with the ActionClient.
+
+use std::fmt;
+use std::rc::Rc;
+
+use action::{Action, ActionTarget};
+use data::{Ability, Alliance, Point2, Tag, Unit, Vector2};
+use observer::{Event, Observation};
+
+/// A named group of units.
+#[derive(Debug, Clone)]
+pub struct Squad {
+    name: String,
+    tags: Vec<Tag>,
+
+    /// Latest snapshot of the units (only the ones that were observed).
+    units: Vec<Rc<Unit>>,
+}
+
+impl Squad {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            tags: vec![],
+
+            units: vec![],
+        }
+    }
+
+    /// Name of the squad.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    /// Tags of the units in the squad.
+    pub fn get_tags(&self) -> &[Tag] {
+        &self.tags
+    }
+    /// Units of the squad as of the last update.
+    ///
+    /// Units that were not in the last observation (ie. units inside of a
+    /// transport) are still part of the squad, but they are not included.
+    pub fn get_units(&self) -> &[Rc<Unit>] {
+        &self.units
+    }
+    /// Whether the squad contains the unit.
+    pub fn contains(&self, tag: Tag) -> bool {
+        self.tags.contains(&tag)
+    }
+    /// Number of units in the squad.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+    /// Whether the squad has no units.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Average position of the units (None if there are no units).
+    pub fn get_center(&self) -> Option<Point2> {
+        if self.units.is_empty() {
+            return None;
+        }
+
+        let sum = self.units
+            .iter()
+            .fold(Vector2::new(0.0, 0.0), |sum, u| {
+                sum + u.get_pos_2d().coords
+            });
+
+        Some(Point2::from_coordinates(sum / self.units.len() as f32))
+    }
+    /// Average distance of the units from the center.
+    pub fn get_spread(&self) -> f32 {
+        match self.get_center() {
+            Some(center) => {
+                self.units
+                    .iter()
+                    .map(|u| (u.get_pos_2d() - center).norm())
+                    .sum::<f32>() / self.units.len() as f32
+            },
+            None => 0.0,
+        }
+    }
+    /// Total health of the units.
+    pub fn get_health(&self) -> f32 {
+        self.units.iter().map(|u| u.get_health()).sum()
+    }
+    /// Total max health of the units.
+    pub fn get_health_cap(&self) -> f32 {
+        self.units.iter().map(|u| u.get_health_cap()).sum()
+    }
+    /// Total shields of the units.
+    pub fn get_shield(&self) -> f32 {
+        self.units.iter().map(|u| u.get_shield()).sum()
+    }
+
+    /// Use an ability with every unit in the squad.
+    pub fn command(&self, ability: Ability) -> Action {
+        Action::new(ability).unit_tags(self.tags.clone())
+    }
+    /// Attack-move the squad to the given point.
+    pub fn attack(&self, target: Point2) -> Action {
+        self.command(Ability::Attack).target(ActionTarget::Location(target))
+    }
+    /// Move the squad to the given point (ignoring enemies on the way).
+    pub fn move_to(&self, target: Point2) -> Action {
+        self.command(Ability::Move).target(ActionTarget::Location(target))
+    }
+}
+
+type Rule = Rc<Fn(&Unit) -> bool>;
+
+/// Keeps track of the squads of the current player.
+#[derive(Clone)]
+pub struct SquadManager {
+    squads: Vec<Squad>,
+    rules: Vec<(Rule, String)>,
+}
+
+impl SquadManager {
+    /// Create a manager without any squads.
+    pub fn new() -> Self {
+        Self {
+            squads: vec![],
+            rules: vec![],
+        }
+    }
+
+    /// Automatically add new units that match the condition to the squad.
+    ///
+    /// Rules are checked in the order they were added and each unit only
+    /// joins the squad of the first rule that matches.
+    pub fn assign<F>(mut self, name: &str, rule: F) -> Self
+    where
+        F: Fn(&Unit) -> bool + 'static,
+    {
+        self.get_or_create(name);
+        self.rules.push((Rc::new(rule), name.to_string()));
+
+        self
+    }
+
+    /// Every squad in the order they were created.
+    pub fn get_squads(&self) -> &[Squad] {
+        &self.squads
+    }
+    /// Squad with the given name.
+    pub fn get_squad(&self, name: &str) -> Option<&Squad> {
+        self.squads.iter().find(|s| s.name == name)
+    }
+    /// Squad that the unit belongs to.
+    pub fn get_squad_of(&self, tag: Tag) -> Option<&Squad> {
+        self.squads.iter().find(|s| s.contains(tag))
+    }
+
+    /// Move a unit into the squad (creating the squad if necessary).
+    ///
+    /// A unit can only be in one squad at a time, so it is removed from its
+    /// previous squad.
+    pub fn add_unit(&mut self, name: &str, tag: Tag) {
+        self.remove_unit(tag);
+        self.get_or_create(name).tags.push(tag);
+    }
+    /// Remove a unit from its squad.
+    pub fn remove_unit(&mut self, tag: Tag) {
+        for squad in &mut self.squads {
+            squad.tags.retain(|t| *t != tag);
+            squad.units.retain(|u| u.get_tag() != tag);
+        }
+    }
+    /// Remove a squad along with all of its units.
+    pub fn remove_squad(&mut self, name: &str) {
+        self.squads.retain(|s| s.name != name);
+    }
+
+    /// Keep the squads up to date with the game events.
+    pub fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::UnitCreated(ref unit) => {
+                let squad = self.rules
+                    .iter()
+                    .find(|&&(ref rule, _)| rule(unit))
+                    .map(|&(_, ref name)| name.clone());
+
+                if let Some(squad) = squad {
+                    self.add_unit(&squad, unit.get_tag());
+                }
+            },
+            Event::UnitDestroyed(ref unit) => {
+                self.remove_unit(unit.get_tag());
+            },
+            Event::UnitOwnerChanged(ref unit, _) => {
+                if unit.get_alliance() != Alliance::Domestic {
+                    self.remove_unit(unit.get_tag());
+                }
+            },
+            _ => (),
+        }
+    }
+
+    /// Refresh the units of each squad from the observation.
+    pub fn update(&mut self, observation: &Observation) {
+        for squad in &mut self.squads {
+            let units =
+                observation.filter_units(|u| squad.contains(u.get_tag()));
+
+            squad.units = units;
+        }
+    }
+
+    fn get_or_create(&mut self, name: &str) -> &mut Squad {
+        match self.squads.iter().position(|s| s.name == name) {
+            Some(i) => &mut self.squads[i],
+            None => {
+                self.squads.push(Squad::new(name));
+                self.squads.last_mut().unwrap()
+            },
+        }
+    }
+}
+
+impl fmt::Debug for SquadManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SquadManager")
+            .field("squads", &self.squads)
+            .field(
+                "rules",
+                &self.rules.iter().map(|r| &r.1).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sc2_proto::raw;
+
+    use data::UnitType;
+    use fixtures::unit_at;
+
+    fn unit(tag: Tag, unit_type: u32, x: f32, y: f32) -> Rc<Unit> {
+        unit_at(tag, unit_type, raw::Alliance::Domestic, x, y)
+    }
+
+    #[test]
+    fn test_assignment() {
+        let mut squads = SquadManager::new()
+            .assign("marines", |u| {
+                u.get_unit_type() == UnitType::TerranMarine
+            })
+            .assign("army", |_| true);
+
+        let marine = unit(1, 48, 10.0, 10.0);
+        let marauder = unit(2, 51, 20.0, 10.0);
+
+        squads.on_event(&Event::UnitCreated(Rc::clone(&marine)));
+        squads.on_event(&Event::UnitCreated(Rc::clone(&marauder)));
+
+        assert_eq!(squads.get_squad("marines").unwrap().get_tags(), &[1]);
+        assert_eq!(squads.get_squad("army").unwrap().get_tags(), &[2]);
+
+        squads.add_unit("army", 1);
+
+        assert!(squads.get_squad("marines").unwrap().is_empty());
+        assert_eq!(squads.get_squad_of(1).unwrap().get_name(), "army");
+
+        squads.on_event(&Event::UnitDestroyed(marauder));
+
+        assert_eq!(squads.get_squad("army").unwrap().get_tags(), &[1]);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut squad = Squad::new("army");
+
+        assert_eq!(squad.get_center(), None);
+
+        squad.units = vec![unit(1, 48, 10.0, 10.0), unit(2, 48, 20.0, 10.0)];
+        squad.tags = vec![1, 2];
+
+        assert_eq!(squad.get_center(), Some(Point2::new(15.0, 10.0)));
+        assert_eq!(squad.get_spread(), 5.0);
+        assert_eq!(squad.get_health(), 90.0);
+    }
+}
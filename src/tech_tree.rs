@@ -0,0 +1,428 @@
+//! Contains a tech tree built from the game data.
+//!
+//! The game data knows the costs and requirements of each unit, but it does
+//! not know which units are able to use which abilities. The TechTree pairs
+//! the game data with UnitType::get_abilities to answer questions like "what
+//! builds a Marauder" or "can I build a Factory right now".
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use futures::prelude::*;
+
+use data::{
+    Ability,
+    AbilityData,
+    Alliance,
+    Unit,
+    UnitType,
+    UnitTypeData,
+    Upgrade,
+    UpgradeData,
+};
+use observer::{Observation, ObserverClient};
+use {Error, IntoProto};
+
+/// Relationships between unit types, abilities, and upgrades.
+#[derive(Debug, Clone)]
+pub struct TechTree {
+    unit_data: Rc<HashMap<UnitType, UnitTypeData>>,
+    ability_data: Rc<HashMap<Ability, AbilityData>>,
+    upgrade_data: Rc<HashMap<Upgrade, UpgradeData>>,
+
+    /// Unit types that are able to use each (generalized) ability.
+    users: HashMap<Ability, Vec<UnitType>>,
+}
+
+impl TechTree {
+    /// Build the tech tree from the game data.
+    pub fn new(
+        unit_data: Rc<HashMap<UnitType, UnitTypeData>>,
+        ability_data: Rc<HashMap<Ability, AbilityData>>,
+        upgrade_data: Rc<HashMap<Upgrade, UpgradeData>>,
+    ) -> Self {
+        let mut tree = Self {
+            unit_data: unit_data,
+            ability_data: ability_data,
+            upgrade_data: upgrade_data,
+
+            users: HashMap::new(),
+        };
+
+        let mut users = HashMap::new();
+
+        for unit_type in tree.unit_data.keys() {
+            for ability in unit_type.get_abilities() {
+                users
+                    .entry(tree.generalize(*ability))
+                    .or_insert_with(|| vec![])
+                    .push(*unit_type);
+            }
+        }
+
+        // the game data is in no particular order, so sort the users by id
+        // to keep the base types (ie. CommandCenter) ahead of their morphs
+        for users in users.values_mut() {
+            users.sort_by_key(|u| u.into_proto().unwrap());
+        }
+
+        tree.users = users;
+
+        tree
+    }
+
+    /// Fetch the game data from the observer and build the tech tree.
+    pub fn load(
+        observer: &ObserverClient,
+    ) -> impl Future<Item = Self, Error = Error> {
+        let observer = observer.clone();
+
+        async_block! {
+            let unit_data = await!(observer.get_unit_data())?;
+            let ability_data = await!(observer.get_ability_data())?;
+            let upgrade_data = await!(observer.get_upgrade_data())?;
+
+            Ok(TechTree::new(unit_data, ability_data, upgrade_data))
+        }
+    }
+
+    /// Game data for the given unit type.
+    pub fn get_unit_data(&self, unit_type: UnitType) -> Option<&UnitTypeData> {
+        self.unit_data.get(&unit_type)
+    }
+
+    /// Game data for the given upgrade.
+    pub fn get_upgrade_data(&self, upgrade: Upgrade) -> Option<&UpgradeData> {
+        self.upgrade_data.get(&upgrade)
+    }
+
+    /// Unit types that are able to use the given ability.
+    pub fn get_users(&self, ability: Ability) -> &[UnitType] {
+        match self.users.get(&self.generalize(ability)) {
+            Some(users) => users,
+            None => &[],
+        }
+    }
+
+    /// Unit types that build, train, or morph into the given unit type.
+    ///
+    /// For add-ons, this is the structure that the add-on attaches to. Unit
+    /// types are sorted by id, so base types come before their morphs.
+    pub fn get_producers(&self, unit_type: UnitType) -> &[UnitType] {
+        match self.unit_data.get(&unit_type) {
+            Some(data) => self.get_users(data.get_ability()),
+            None => &[],
+        }
+    }
+
+    /// Structures that are able to research the given upgrade.
+    pub fn get_researchers(&self, upgrade: Upgrade) -> &[UnitType] {
+        match self.upgrade_data.get(&upgrade) {
+            Some(data) => self.get_users(data.get_ability()),
+            None => &[],
+        }
+    }
+
+    /// Unit type that must exist before the given unit type can be built.
+    ///
+    /// If the unit type requires an attached add-on, then this is the add-on
+    /// (see requires_add_on).
+    pub fn get_requirement(&self, unit_type: UnitType) -> Option<UnitType> {
+        match self.unit_data.get(&unit_type) {
+            Some(data) if data.get_tech_requirement() != UnitType::Invalid => {
+                Some(data.get_tech_requirement())
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether the requirement must be attached to the producer (ie. units
+    /// that need a TechLab).
+    pub fn requires_add_on(&self, unit_type: UnitType) -> bool {
+        match self.unit_data.get(&unit_type) {
+            Some(data) => data.get_require_attached(),
+            None => false,
+        }
+    }
+
+    /// Every unit type that needs to exist before the given unit type can be
+    /// built, including the producer and the producer's own prerequisites.
+    ///
+    /// Where there are multiple producers (ie. CommandCenter and
+    /// OrbitalCommand both train SCVs), only the first one is followed.
+    pub fn get_prerequisites(&self, unit_type: UnitType) -> Vec<UnitType> {
+        let mut prerequisites = vec![];
+        let mut visited = HashSet::new();
+        let mut pending = vec![unit_type];
+
+        visited.insert(unit_type);
+
+        while let Some(next) = pending.pop() {
+            let requirement = self.get_requirement(next);
+            let producer = self.get_producers(next).first().map(|p| *p);
+
+            for u in requirement.into_iter().chain(producer.into_iter()) {
+                if visited.insert(u) {
+                    prerequisites.push(u);
+                    pending.push(u);
+                }
+            }
+        }
+
+        prerequisites
+    }
+
+    /// Whether a unit of type `have` counts as `need` for requirements.
+    ///
+    /// This takes tech aliases into account (ie. a Lair counts as a Hatchery
+    /// and a lowered SupplyDepot still counts as a SupplyDepot).
+    pub fn satisfies(&self, have: UnitType, need: UnitType) -> bool {
+        if have == need {
+            return true;
+        }
+
+        match self.unit_data.get(&have) {
+            Some(data) => {
+                data.get_tech_alias().contains(&need)
+                    || data.get_unit_alias() == need
+            },
+            None => false,
+        }
+    }
+
+    /// Whether the current player has enough resources and supply for the
+    /// given unit type.
+    pub fn can_afford(
+        &self,
+        unit_type: UnitType,
+        observation: &Observation,
+    ) -> bool {
+        let data = match self.unit_data.get(&unit_type) {
+            Some(data) => data,
+            None => return false,
+        };
+
+        let food_left = observation.get_food_cap() as f32
+            - observation.get_food_used() as f32;
+
+        observation.get_minerals() >= data.get_mineral_cost()
+            && observation.get_vespene() >= data.get_vespene_cost()
+            && data.get_food_required() <= food_left
+    }
+
+    /// Whether the current player can afford the given upgrade and has a
+    /// structure that can research it.
+    pub fn can_research(
+        &self,
+        upgrade: Upgrade,
+        observation: &Observation,
+    ) -> bool {
+        let data = match self.upgrade_data.get(&upgrade) {
+            Some(data) => data,
+            None => return false,
+        };
+
+        !observation.get_upgrades().contains(&upgrade)
+            && observation.get_minerals() >= data.get_mineral_cost()
+            && observation.get_vespene() >= data.get_vespene_cost()
+            && !self.get_idle_producers(
+                self.get_researchers(upgrade),
+                None,
+                observation,
+            ).is_empty()
+    }
+
+    /// Whether the current player can afford the given unit type, meets its
+    /// requirements, and has a producer for it.
+    pub fn can_build(
+        &self,
+        unit_type: UnitType,
+        observation: &Observation,
+    ) -> bool {
+        if !self.can_afford(unit_type, observation) {
+            return false;
+        }
+
+        let requirement = self.get_requirement(unit_type);
+
+        if let Some(requirement) = requirement {
+            let has_requirement = !observation
+                .filter_units(|u| {
+                    is_finished(u)
+                        && self.satisfies(u.get_unit_type(), requirement)
+                })
+                .is_empty();
+
+            if !self.requires_add_on(unit_type) && !has_requirement {
+                return false;
+            }
+        }
+
+        let add_on = if self.requires_add_on(unit_type) {
+            requirement
+        } else {
+            None
+        };
+
+        !self.get_idle_producers(
+            self.get_producers(unit_type),
+            add_on,
+            observation,
+        ).is_empty()
+    }
+
+    /// Finished units of the current player that are one of the given types
+    /// and are not busy producing anything else.
+    ///
+    /// If an add-on is given, then only producers with that add-on attached
+    /// are returned.
+    pub fn get_idle_producers(
+        &self,
+        producers: &[UnitType],
+        add_on: Option<UnitType>,
+        observation: &Observation,
+    ) -> Vec<Rc<Unit>> {
+        observation.filter_units(|u| {
+            is_finished(u) && producers.contains(&u.get_unit_type())
+                && !self.is_producing(u)
+                && match add_on {
+                    Some(add_on) => self.has_add_on(u, add_on, observation),
+                    None => true,
+                }
+        })
+    }
+
+    /// Whether the unit is currently producing something (as opposed to
+    /// moving or harvesting, which can be interrupted).
+    fn is_producing(&self, unit: &Unit) -> bool {
+        if unit.get_unit_type().get_abilities().contains(&Ability::Move) {
+            // mobile units (ie. workers and larva) can always be redirected
+            false
+        } else {
+            !unit.get_orders().is_empty()
+        }
+    }
+
+    /// Whether the unit has an attached add-on of the given type.
+    fn has_add_on(
+        &self,
+        unit: &Unit,
+        add_on: UnitType,
+        observation: &Observation,
+    ) -> bool {
+        let tag = unit.get_add_on_tag();
+
+        tag != 0
+            && !observation
+                .filter_units(|u| {
+                    u.get_tag() == tag && is_finished(u)
+                        && self.satisfies(u.get_unit_type(), add_on)
+                })
+                .is_empty()
+    }
+
+    fn generalize(&self, ability: Ability) -> Ability {
+        match self.ability_data.get(&ability) {
+            Some(data) => data.get_generalized_ability(),
+            None => ability,
+        }
+    }
+}
+
+/// Whether the unit belongs to the current player and is fully built.
+fn is_finished(unit: &Unit) -> bool {
+    unit.get_alliance() == Alliance::Domestic
+        && unit.get_build_progress() >= 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use fixtures::unit_data;
+
+    fn tree() -> TechTree {
+        TechTree::new(
+            Rc::from(
+                vec![
+                    unit_data(18, 318, 0, 0),  // CommandCenter
+                    unit_data(45, 524, 0, 0),  // SCV
+                    unit_data(19, 319, 0, 0),  // SupplyDepot
+                    unit_data(21, 321, 19, 0), // Barracks
+                    unit_data(48, 560, 0, 0),  // Marine
+                ].into_iter()
+                    .collect::<HashMap<_, _>>(),
+            ),
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+        )
+    }
+
+    #[test]
+    fn test_producers() {
+        let tree = tree();
+
+        assert_eq!(
+            tree.get_producers(UnitType::TerranMarine),
+            &[UnitType::TerranBarracks]
+        );
+        assert_eq!(
+            tree.get_producers(UnitType::TerranBarracks),
+            &[UnitType::TerranScv]
+        );
+        assert_eq!(
+            tree.get_requirement(UnitType::TerranBarracks),
+            Some(UnitType::TerranSupplyDepot)
+        );
+    }
+
+    #[test]
+    fn test_prerequisites() {
+        let tree = tree();
+
+        let prerequisites = tree
+            .get_prerequisites(UnitType::TerranMarine)
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            prerequisites,
+            vec![
+                UnitType::TerranBarracks,
+                UnitType::TerranSupplyDepot,
+                UnitType::TerranScv,
+                UnitType::TerranCommandCenter,
+            ].into_iter()
+                .collect()
+        );
+    }
+    #[test]
+    fn test_multiple_producers() {
+        let tree = TechTree::new(
+            Rc::from(
+                vec![
+                    unit_data(132, 1516, 0, 0), // OrbitalCommand
+                    unit_data(130, 1450, 0, 0), // PlanetaryFortress
+                    unit_data(18, 318, 0, 0),   // CommandCenter
+                    unit_data(45, 524, 0, 0),   // SCV
+                ].into_iter()
+                    .collect::<HashMap<_, _>>(),
+            ),
+            Rc::from(HashMap::new()),
+            Rc::from(HashMap::new()),
+        );
+
+        assert_eq!(
+            tree.get_producers(UnitType::TerranScv),
+            &[
+                UnitType::TerranCommandCenter,
+                UnitType::TerranPlanetaryFortress,
+                UnitType::TerranOrbitalCommand,
+            ]
+        );
+        assert_eq!(
+            tree.get_prerequisites(UnitType::TerranScv),
+            vec![UnitType::TerranCommandCenter]
+        );
+    }
+}
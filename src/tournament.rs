@@ -0,0 +1,684 @@
+//! Contains a coordinator that plays a whole roster of competitors against
+//! each other.
+//!
+//! The Tournament reuses the same two game instances for every game, keeps
+//! track of the wins, losses and Elo rating of each competitor and returns a
+//! results table that can be written as CSV or JSON. Competitors can be any
+//! mix of bots (AgentBuilder) and built-in AI (OpponentBuilder), although two
+//! built-in AI are never paired with each other.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use futures::prelude::*;
+use futures::unsync::mpsc;
+use tokio_core::reactor;
+
+use data::{GameResult, GameSetup, Map};
+use launcher::{Launcher, LauncherSettings};
+use services::melee_service::{
+    Arena,
+    MeleeClient,
+    MeleeCompetitor,
+    UpdateScheme,
+};
+use {Error, ErrorKind, Result};
+
+/// Rating that every competitor starts with.
+const INITIAL_ELO: f32 = 1500.0;
+/// How much a single game can change a rating.
+const ELO_K_FACTOR: f32 = 32.0;
+
+/// How the games of a tournament are scheduled.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TournamentFormat {
+    /// Every competitor plays every other competitor the given number of
+    /// times, alternating sides between games.
+    RoundRobin(u32),
+    /// Competitors with similar scores are paired for the given number of
+    /// rounds, avoiding rematches where possible.
+    Swiss(u32),
+}
+
+/// Build a Tournament coordinator.
+pub struct TournamentBuilder {
+    competitors: Vec<(String, Box<MeleeCompetitor>)>,
+    maps: Vec<GameSetup>,
+
+    format: TournamentFormat,
+    launcher_settings: Option<LauncherSettings>,
+    update_scheme: UpdateScheme,
+    output: Option<PathBuf>,
+    handle: Option<reactor::Handle>,
+}
+
+impl TournamentBuilder {
+    /// Start building a Tournament coordinator.
+    pub fn new() -> Self {
+        Self {
+            competitors: vec![],
+            maps: vec![],
+
+            format: TournamentFormat::RoundRobin(2),
+            launcher_settings: None,
+            update_scheme: UpdateScheme::Interval(1),
+            output: None,
+            handle: None,
+        }
+    }
+
+    /// Add a competitor under the given name.
+    pub fn add_competitor<T>(mut self, name: &str, competitor: T) -> Self
+    where
+        T: MeleeCompetitor + Sized + 'static,
+    {
+        self.competitors
+            .push((name.to_string(), Box::new(competitor)));
+        self
+    }
+
+    /// Add a map to the map pool (games cycle through the pool in order).
+    ///
+    /// Game i of the tournament is played on the current map of setup
+    /// i % pool size. The rotation of each setup (GameSetup::add_map) is not
+    /// used, so add every map to the pool instead.
+    pub fn add_map(mut self, game: GameSetup) -> Self {
+        self.maps.push(game);
+        self
+    }
+
+    /// How games are scheduled (defaults to a double round robin).
+    pub fn format(self, format: TournamentFormat) -> Self {
+        Self {
+            format: format,
+            ..self
+        }
+    }
+
+    /// The settings for the launcher.
+    pub fn launcher_settings(self, settings: LauncherSettings) -> Self {
+        Self {
+            launcher_settings: Some(settings),
+            ..self
+        }
+    }
+
+    /// Step the game instance with a discrete interval
+    pub fn step_interval(self, steps: u32) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Interval(steps),
+            ..self
+        }
+    }
+
+    /// Step the bot as fast as possible
+    pub fn step_realtime(self) -> Self {
+        Self {
+            update_scheme: UpdateScheme::Realtime,
+            ..self
+        }
+    }
+
+    /// Rewrite the results to the given file after every game.
+    ///
+    /// The results are written as JSON if the file has a .json extension and
+    /// as CSV otherwise, so an interrupted tournament still leaves a table
+    /// behind.
+    pub fn output(self, path: PathBuf) -> Self {
+        Self {
+            output: Some(path),
+            ..self
+        }
+    }
+
+    /// Provide a handle to spawn background tasks.
+    pub fn handle(self, handle: &reactor::Handle) -> Self {
+        Self {
+            handle: Some(handle.clone()),
+            ..self
+        }
+    }
+
+    /// Build the Tournament coordinator.
+    pub fn create(self) -> Result<Tournament> {
+        if self.launcher_settings.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "TournamentBuilder needs LauncherSettings".to_string()
+            ))
+        } else if self.maps.is_empty() {
+            bail!(ErrorKind::MissingRequirement(
+                "TournamentBuilder needs at least one map".to_string()
+            ))
+        } else if self.handle.is_none() {
+            bail!(ErrorKind::MissingRequirement(
+                "TournamentBuilder needs a reactor handle".to_string()
+            ))
+        } else if self.competitors.len() < 2 {
+            bail!(ErrorKind::InvalidMatch(
+                "A tournament needs at least two competitors".to_string()
+            ))
+        }
+
+        let handle = self.handle.unwrap();
+
+        let mut names = vec![];
+        let mut agents = vec![];
+
+        for (name, mut competitor) in self.competitors {
+            let (tx, rx) = mpsc::channel(10);
+
+            competitor.spawn(&handle, rx)?;
+
+            names.push(name);
+            agents.push(MeleeClient::new(tx));
+        }
+
+        Ok(Tournament {
+            agents: agents,
+            maps: self.maps,
+            format: self.format,
+            update_scheme: self.update_scheme,
+            output: self.output,
+            launcher: Launcher::create(self.launcher_settings.unwrap())?,
+
+            results: TournamentResults::new(names),
+        })
+    }
+}
+
+/// Plays every game of a tournament.
+///
+/// The future resolves to the final results.
+pub struct Tournament {
+    agents: Vec<MeleeClient>,
+    maps: Vec<GameSetup>,
+    format: TournamentFormat,
+    update_scheme: UpdateScheme,
+    output: Option<PathBuf>,
+    launcher: Launcher,
+
+    results: TournamentResults,
+}
+
+impl IntoFuture for Tournament {
+    type Item = TournamentResults;
+    type Error = Error;
+    type Future = Box<Future<Item = Self::Item, Error = Self::Error>>;
+
+    fn into_future(self) -> Self::Future {
+        Box::new(self.run())
+    }
+}
+
+impl Tournament {
+    #[async]
+    fn run(self) -> Result<TournamentResults> {
+        let agents = self.agents;
+        let maps = self.maps;
+        let update_scheme = self.update_scheme;
+        let output = self.output;
+
+        let mut results = self.results;
+        let mut arena = Arena::new(self.launcher);
+
+        // built-in AI cannot play each other
+        let mut is_computer = vec![];
+
+        for i in 0..agents.len() {
+            let game = Some(maps[0].clone());
+            let setup = await!(agents[i].clone().get_player_setup(game))?;
+
+            is_computer.push(setup.is_computer());
+        }
+
+        let rounds = match self.format {
+            TournamentFormat::RoundRobin(_) => 1,
+            TournamentFormat::Swiss(rounds) => rounds,
+        };
+
+        for _ in 0..rounds {
+            let pairings = match self.format {
+                TournamentFormat::RoundRobin(cycles) => {
+                    round_robin(agents.len(), cycles)
+                        .into_iter()
+                        .filter(|&(a, b)| !(is_computer[a] && is_computer[b]))
+                        .collect()
+                },
+                TournamentFormat::Swiss(_) => {
+                    results.get_swiss_pairings(&is_computer)
+                },
+            };
+
+            for (player1, player2) in pairings {
+                let game = maps[results.games.len() % maps.len()].clone();
+                let (next_arena, outcome) = await!(arena.play(
+                    agents[player1].clone(),
+                    agents[player2].clone(),
+                    game.clone(),
+                    [update_scheme, update_scheme]
+                ))?;
+
+                arena = next_arena;
+                results.record(player1, player2, &game, outcome[0]);
+
+                if let Some(ref path) = output {
+                    results.write(path)?;
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// The record of a single competitor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    name: String,
+    wins: u32,
+    losses: u32,
+    ties: u32,
+    elo: f32,
+}
+
+impl Standing {
+    /// Name of the competitor.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    /// Number of games won.
+    pub fn get_wins(&self) -> u32 {
+        self.wins
+    }
+    /// Number of games lost.
+    pub fn get_losses(&self) -> u32 {
+        self.losses
+    }
+    /// Number of games tied (or left undecided).
+    pub fn get_ties(&self) -> u32 {
+        self.ties
+    }
+    /// Number of games played.
+    pub fn get_games(&self) -> u32 {
+        self.wins + self.losses + self.ties
+    }
+    /// Fraction of the games won (ties count as half a win).
+    pub fn get_win_rate(&self) -> f32 {
+        match self.get_games() {
+            0 => 0.0,
+            games => (self.wins as f32 + self.ties as f32 / 2.0) / games as f32,
+        }
+    }
+    /// Current Elo rating.
+    pub fn get_elo(&self) -> f32 {
+        self.elo
+    }
+
+    fn get_score(&self) -> f32 {
+        self.wins as f32 + self.ties as f32 / 2.0
+    }
+}
+
+/// A game that was played in the tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    player1: String,
+    player2: String,
+    map: String,
+    result: GameResult,
+}
+
+impl GameRecord {
+    /// Name of the first player (the one that created the game).
+    pub fn get_player1(&self) -> &str {
+        &self.player1
+    }
+    /// Name of the second player.
+    pub fn get_player2(&self) -> &str {
+        &self.player2
+    }
+    /// Name of the map.
+    pub fn get_map(&self) -> &str {
+        &self.map
+    }
+    /// Result from the perspective of the first player.
+    pub fn get_result(&self) -> GameResult {
+        self.result
+    }
+}
+
+/// Standings and games of a tournament.
+#[derive(Debug, Clone)]
+pub struct TournamentResults {
+    standings: Vec<Standing>,
+    games: Vec<GameRecord>,
+
+    /// Indices of the players of each game (for pairings).
+    pairs: Vec<(usize, usize)>,
+}
+
+impl TournamentResults {
+    fn new(names: Vec<String>) -> Self {
+        Self {
+            standings: names
+                .into_iter()
+                .map(|name| Standing {
+                    name: name,
+                    wins: 0,
+                    losses: 0,
+                    ties: 0,
+                    elo: INITIAL_ELO,
+                })
+                .collect(),
+            games: vec![],
+
+            pairs: vec![],
+        }
+    }
+
+    /// Standings of every competitor, from the highest rating to the lowest.
+    pub fn get_standings(&self) -> Vec<&Standing> {
+        let mut standings = self.standings.iter().collect::<Vec<_>>();
+
+        standings.sort_by(|a, b| b.elo.partial_cmp(&a.elo).unwrap());
+        standings
+    }
+    /// Every game in the order they were played.
+    pub fn get_games(&self) -> &[GameRecord] {
+        &self.games
+    }
+
+    /// Format the standings as CSV (with a header row).
+    pub fn to_csv(&self) -> String {
+        let mut csv = "name,games,wins,losses,ties,win_rate,elo\n".to_string();
+
+        for s in self.get_standings() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.3},{:.1}\n",
+                escape_csv(&s.name),
+                s.get_games(),
+                s.wins,
+                s.losses,
+                s.ties,
+                s.get_win_rate(),
+                s.elo
+            ));
+        }
+
+        csv
+    }
+
+    /// Format the standings and games as JSON.
+    pub fn to_json(&self) -> String {
+        let standings = self.get_standings()
+            .iter()
+            .map(|s| {
+                format!(
+                    "{{\"name\":{},\"games\":{},\"wins\":{},\"losses\":{},\
+                     \"ties\":{},\"win_rate\":{:.3},\"elo\":{:.1}}}",
+                    escape_json(&s.name),
+                    s.get_games(),
+                    s.wins,
+                    s.losses,
+                    s.ties,
+                    s.get_win_rate(),
+                    s.elo
+                )
+            })
+            .collect::<Vec<_>>();
+        let games = self.games
+            .iter()
+            .map(|g| {
+                format!(
+                    "{{\"player1\":{},\"player2\":{},\"map\":{},\
+                     \"result\":\"{:?}\"}}",
+                    escape_json(&g.player1),
+                    escape_json(&g.player2),
+                    escape_json(&g.map),
+                    g.result
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"standings\":[{}],\"games\":[{}]}}",
+            standings.join(","),
+            games.join(",")
+        )
+    }
+
+    /// Record a game and update the ratings of both players.
+    fn record(
+        &mut self,
+        player1: usize,
+        player2: usize,
+        game: &GameSetup,
+        result: GameResult,
+    ) {
+        let score = match result {
+            GameResult::Win => {
+                self.standings[player1].wins += 1;
+                self.standings[player2].losses += 1;
+                1.0
+            },
+            GameResult::Loss => {
+                self.standings[player1].losses += 1;
+                self.standings[player2].wins += 1;
+                0.0
+            },
+            GameResult::Tie | GameResult::Undecided => {
+                self.standings[player1].ties += 1;
+                self.standings[player2].ties += 1;
+                0.5
+            },
+        };
+
+        let (elo1, elo2) =
+            (self.standings[player1].elo, self.standings[player2].elo);
+        let expected = 1.0 / (1.0 + 10f32.powf((elo2 - elo1) / 400.0));
+        let change = ELO_K_FACTOR * (score - expected);
+
+        self.standings[player1].elo += change;
+        self.standings[player2].elo -= change;
+
+        self.games.push(GameRecord {
+            player1: self.standings[player1].name.clone(),
+            player2: self.standings[player2].name.clone(),
+            map: match *game.get_map() {
+                Map::LocalMap(ref path) => path.file_stem()
+                    .map_or(String::new(), |s| s.to_string_lossy().into()),
+                Map::BlizzardMap(ref name) => name.clone(),
+            },
+            result: result,
+        });
+        self.pairs.push((player1, player2));
+    }
+
+    /// Pair competitors with similar scores that have met the least.
+    ///
+    /// Whoever has played first less often gets to play first. Built-in AI
+    /// are never paired with each other, so competitors without a partner
+    /// (the lowest one with an odd number of competitors) sit out.
+    fn get_swiss_pairings(&self, is_computer: &[bool]) -> Vec<(usize, usize)> {
+        let mut order = (0..self.standings.len()).collect::<Vec<_>>();
+
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.standings[a], &self.standings[b]);
+
+            (b.get_score(), b.elo)
+                .partial_cmp(&(a.get_score(), a.elo))
+                .unwrap()
+        });
+
+        let meetings = |a: usize, b: usize| {
+            self.pairs
+                .iter()
+                .filter(|&&p| p == (a, b) || p == (b, a))
+                .count()
+        };
+        let firsts =
+            |a: usize| self.pairs.iter().filter(|p| p.0 == a).count();
+
+        let mut pairings = vec![];
+
+        while order.len() > 1 {
+            let a = order.remove(0);
+            let i = match (0..order.len())
+                .filter(|&i| !(is_computer[a] && is_computer[order[i]]))
+                .min_by_key(|&i| (meetings(a, order[i]), i))
+            {
+                Some(i) => i,
+                None => continue,
+            };
+            let b = order.remove(i);
+
+            if firsts(a) <= firsts(b) {
+                pairings.push((a, b));
+            } else {
+                pairings.push((b, a));
+            }
+        }
+
+        pairings
+    }
+
+    fn write(&self, path: &PathBuf) -> Result<()> {
+        let contents = match path.extension() {
+            Some(ext) if ext == "json" => self.to_json(),
+            _ => self.to_csv(),
+        };
+
+        File::create(path)?.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Every pairing of n competitors, repeated for each cycle.
+///
+/// Sides alternate between pairs and between cycles, so a double round robin
+/// plays each pairing once from each side.
+fn round_robin(n: usize, cycles: u32) -> Vec<(usize, usize)> {
+    let mut pairings = vec![];
+
+    for cycle in 0..cycles as usize {
+        for i in 0..n {
+            for j in i + 1..n {
+                if (i + j + cycle) % 2 == 0 {
+                    pairings.push((i, j));
+                } else {
+                    pairings.push((j, i));
+                }
+            }
+        }
+    }
+
+    pairings
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = "\"".to_string();
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            },
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(n: usize) -> TournamentResults {
+        TournamentResults::new((0..n).map(|i| format!("bot{}", i)).collect())
+    }
+
+    fn game() -> GameSetup {
+        GameSetup::new(Map::BlizzardMap("Acropolis".to_string()))
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let pairings = round_robin(3, 2);
+
+        assert_eq!(pairings.len(), 6);
+
+        for &(a, b) in &pairings {
+            assert!(pairings.contains(&(b, a)));
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut results = results(2);
+
+        results.record(0, 1, &game(), GameResult::Win);
+        results.record(1, 0, &game(), GameResult::Tie);
+
+        let standings = results.get_standings();
+
+        assert_eq!(standings[0].get_name(), "bot0");
+        assert_eq!(standings[0].get_win_rate(), 0.75);
+        assert!(
+            (standings[0].get_elo() + standings[1].get_elo() - 3000.0).abs()
+                < 0.01
+        );
+
+        assert_eq!(
+            results.to_csv().lines().nth(1),
+            Some("bot0,2,1,0,1,0.750,1514.5")
+        );
+        assert!(results.to_json().contains("\"result\":\"Tie\""));
+    }
+
+    #[test]
+    fn test_swiss_pairings() {
+        let mut results = results(4);
+
+        results.record(0, 1, &game(), GameResult::Win);
+        results.record(2, 3, &game(), GameResult::Win);
+
+        // winners meet winners
+        assert_eq!(
+            results.get_swiss_pairings(&[false; 4]),
+            vec![(0, 2), (1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_swiss_pairings_computers() {
+        let mut results = results(5);
+        let is_computer = [false, true, true, false, true];
+
+        results.record(1, 0, &game(), GameResult::Win);
+        results.record(2, 3, &game(), GameResult::Win);
+
+        // the winning computers are kept apart and the spare one sits out
+        assert_eq!(
+            results.get_swiss_pairings(&is_computer),
+            vec![(3, 1), (0, 2)]
+        );
+
+        // only computers left means nobody plays
+        assert_eq!(results.get_swiss_pairings(&[true; 5]), vec![]);
+    }
+}